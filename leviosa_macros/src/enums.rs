@@ -0,0 +1,108 @@
+use proc_macro::TokenStream;
+
+use inflector::Inflector;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Ident, Lit, Meta, MetaNameValue, NestedMeta};
+
+// Reads `key = "value"` out of the #[sqlx(...)] attributes, so generated SQL agrees with
+// what sqlx encodes.
+fn sqlx_attribute(attrs: &[Attribute], key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("sqlx"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            })) if path.is_ident(key) => Some(value.value()),
+            _ => None,
+        })
+}
+
+fn rename(variant: &str, rename_all: Option<&str>) -> Result<String, String> {
+    Ok(match rename_all {
+        None => variant.to_string(),
+        Some("lowercase") => variant.to_lowercase(),
+        Some("UPPERCASE") => variant.to_uppercase(),
+        Some("snake_case") => variant.to_snake_case(),
+        Some("SCREAMING_SNAKE_CASE") => variant.to_screaming_snake_case(),
+        Some("kebab-case") => variant.to_kebab_case(),
+        Some("camelCase") => variant.to_camel_case(),
+        Some("PascalCase") => variant.to_pascal_case(),
+        Some(other) => return Err(format!("leviosa: unsupported rename_all `{}`", other)),
+    })
+}
+
+pub fn enum_methods(name: &Ident, input: &DeriveInput) -> TokenStream {
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => unreachable!(),
+    };
+
+    let type_name = sqlx_attribute(&input.attrs, "type_name").unwrap_or_else(|| name.to_string());
+    let rename_all = sqlx_attribute(&input.attrs, "rename_all");
+
+    let mut variant_names = Vec::new();
+    let mut values = Vec::new();
+    for variant in variants {
+        if !variant.fields.is_empty() {
+            return syn::Error::new_spanned(variant, "leviosa: enum variants can not hold data")
+                .to_compile_error()
+                .into();
+        }
+        let value = match sqlx_attribute(&variant.attrs, "rename") {
+            Some(value) => value,
+            None => match rename(&variant.ident.to_string(), rename_all.as_deref()) {
+                Ok(value) => value,
+                Err(message) => {
+                    return syn::Error::new_spanned(name, message)
+                        .to_compile_error()
+                        .into()
+                }
+            },
+        };
+        variant_names.push(&variant.ident);
+        values.push(value);
+    }
+
+    let labels = values
+        .iter()
+        .map(|value| format!("'{}'", value.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let create_type_sql = format!("CREATE TYPE {} AS ENUM ({})", type_name, labels);
+
+    let output = quote! {
+        #input
+
+        impl #name {
+            /// The `CREATE TYPE` statement for the enum, labels follow the sqlx renames.
+            pub fn create_type_sql() -> &'static str {
+                #create_type_sql
+            }
+
+            /// The label stored for the variant, for binding the enum as text.
+            pub fn as_sql_str(&self) -> &'static str {
+                match self {
+                    #( #name::#variant_names => #values ),*
+                }
+            }
+        }
+
+        impl ::leviosa::SqlEnum for #name {
+            const TYPE_NAME: &'static str = #type_name;
+
+            fn as_sql_str(&self) -> &'static str {
+                #name::as_sql_str(self)
+            }
+        }
+    };
+
+    TokenStream::from(output)
+}
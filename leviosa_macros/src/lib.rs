@@ -0,0 +1,128 @@
+use inflector::Inflector;
+use proc_macro::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::Ident;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, ItemStruct, Lit, Meta,
+    MetaNameValue, NestedMeta, PathArguments, Token, Type, TypePath,
+};
+
+mod enums;
+mod many_to_many;
+mod standard;
+mod utils;
+
+#[derive(Debug)]
+pub(crate) struct LeviosaArgs {
+    many_to_many: bool,
+    pub(crate) setter_prefix: String,
+    pub(crate) getters: bool,
+    pub(crate) cursor: Option<Vec<String>>,
+    pub(crate) strict_delete: bool,
+    pub(crate) quote_identifiers: bool,
+    pub(crate) timestamps: bool,
+    pub(crate) checked: bool,
+    pub(crate) hooks: bool,
+    pub(crate) filter: bool,
+    pub(crate) changeset: bool,
+    pub(crate) view: bool,
+}
+
+impl Parse for LeviosaArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = Punctuated::<NestedMeta, Token![,]>::parse_terminated(input)?;
+
+        let mut leviosa_args = LeviosaArgs {
+            many_to_many: false,
+            setter_prefix: String::from("update"),
+            getters: true,
+            cursor: None,
+            strict_delete: false,
+            quote_identifiers: false,
+            timestamps: false,
+            checked: false,
+            hooks: false,
+            filter: false,
+            changeset: false,
+            view: false,
+        };
+
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("many_to_many") => {
+                    leviosa_args.many_to_many = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("no_getters") => {
+                    leviosa_args.getters = false;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("strict_delete") => {
+                    leviosa_args.strict_delete = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("quote_identifiers") => {
+                    leviosa_args.quote_identifiers = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("timestamps") => {
+                    leviosa_args.timestamps = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("checked") => {
+                    leviosa_args.checked = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hooks") => {
+                    leviosa_args.hooks = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("filter") => {
+                    leviosa_args.filter = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("changeset") => {
+                    leviosa_args.changeset = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("view") => {
+                    leviosa_args.view = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(value),
+                    ..
+                })) if path.is_ident("setter_prefix") => {
+                    leviosa_args.setter_prefix = value.value();
+                }
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(value),
+                    ..
+                })) if path.is_ident("cursor") => {
+                    let columns = value
+                        .value()
+                        .split(',')
+                        .map(|column| column.trim().to_string())
+                        .collect();
+                    leviosa_args.cursor = Some(columns);
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(other, "leviosa: unknown argument"));
+                }
+            }
+        }
+
+        Ok(leviosa_args)
+    }
+}
+
+#[proc_macro_attribute]
+pub fn leviosa(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    let args = parse_macro_input!(_attr as LeviosaArgs);
+    let name = &input.ident;
+
+    if let Data::Enum(_) = &input.data {
+        enums::enum_methods(name, &input)
+    } else if args.many_to_many {
+        many_to_many::many_to_many_methods(name, &input)
+    } else {
+        standard::standard_methods(name, &input, &args)
+    }
+}
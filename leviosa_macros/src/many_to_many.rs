@@ -6,7 +6,10 @@ use quote::quote;
 use syn::Ident;
 use syn::{Data, DeriveInput, Fields};
 
-use crate::utils::{extract_relation_generic_type, is_field_type, type_to_string_identifier};
+use crate::utils::{
+    extract_relation_generic_type, is_field_type, strip_leviosa_attributes,
+    type_to_string_identifier,
+};
 
 pub fn many_to_many_methods(name: &Ident, input: &DeriveInput) -> TokenStream {
     let struct_name_snake_case = name.to_string().to_snake_case();
@@ -71,8 +74,10 @@ pub fn many_to_many_methods(name: &Ident, input: &DeriveInput) -> TokenStream {
         quote! {}
     };
 
+    let output = strip_leviosa_attributes(input);
+
     let many_to_many = quote! {
-        #input
+        #output
 
 
         impl #name {
@@ -0,0 +1,1896 @@
+use inflector::Inflector;
+use proc_macro::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::parse::Parser;
+use syn::Ident;
+use syn::{Data, DeriveInput, Fields};
+
+use crate::utils::{
+    bind_value, column_definition, column_options, derives, encrypt_error, extract_generic_type,
+    extract_relation_generic_type, generate_error, generates_uuid_v4, group_keys, has_attribute,
+    has_many_relations, intern_error, is_bool_flag, is_bytes, is_field_type, is_inserted,
+    is_json_value, is_optional_bytes, is_skipped, is_string, on_delete_error, placeholder_cast,
+    primary_key_field, primary_key_type, quote_identifier, read_as_error, select_expression,
+    strip_leviosa_attributes, timestamp_cast_error, try_from_column, try_from_error,
+};
+use crate::LeviosaArgs;
+
+pub fn standard_methods(name: &Ident, input: &DeriveInput, args: &LeviosaArgs) -> TokenStream {
+    let struct_name_snake_case = name.to_string().to_snake_case();
+    // Table and column names as they appear in generated SQL, see #[leviosa(quote_identifiers)].
+    let quote_ident = |identifier: &str| quote_identifier(identifier, args.quote_identifiers);
+    let table_name = quote_ident(&struct_name_snake_case);
+    let primary_key_ident = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => match primary_key_field(fields) {
+                Some(field) => field.ident.clone().unwrap(),
+                None => {
+                    return syn::Error::new_spanned(
+                        name,
+                        "leviosa: no primary key found; add an `id` field or `#[primary_key]`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            },
+            _ => format_ident!("id"),
+        },
+        _ => format_ident!("id"),
+    };
+    let id_column = quote_ident(&primary_key_ident.to_string());
+    if args.view && (args.hooks || args.changeset || args.timestamps) {
+        return syn::Error::new_spanned(
+            name,
+            "leviosa: `view` structs are read-only, `hooks`, `changeset` and `timestamps` write",
+        )
+        .to_compile_error()
+        .into();
+    }
+    // Columns are selected by name rather than `*` so reads can convert flag columns back.
+    let select_list = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .filter(|f| !is_skipped(f))
+                .map(|f| select_expression(f, args.quote_identifiers))
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => String::from("*"),
+        },
+        _ => String::from("*"),
+    };
+    if let Data::Struct(data) = &input.data {
+        let field_error = |field| {
+            timestamp_cast_error(field)
+                .or_else(|| read_as_error(field))
+                .or_else(|| intern_error(field))
+                .or_else(|| generate_error(field))
+                .or_else(|| encrypt_error(field))
+                .or_else(|| try_from_error(field))
+                .or_else(|| on_delete_error(field))
+        };
+        if let Some(error) = data.fields.iter().find_map(field_error) {
+            return error.to_compile_error().into();
+        }
+    }
+    // Per instance caches for #[has_many] relations, stored in hidden fields.
+    let has_many = match has_many_relations(input) {
+        Ok(relations) => relations,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let cache_fields: Vec<_> = has_many
+        .iter()
+        .map(|relation| format_ident!("__leviosa_{}_cache", relation.accessor()))
+        .collect();
+
+    let methods = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => fields.named.iter().filter(|f| !is_skipped(f)).map(|f| {
+                let field_name = f.ident.as_ref().unwrap();
+
+                let ty = &f.ty;
+                let get_fn_name = format_ident!("get_by_{}", field_name);
+                let update_fn_name = format_ident!("{}_{}", args.setter_prefix, field_name);
+                let load_relation_fn_name = format_ident!("load_{}", field_name);
+                let cast = placeholder_cast(f);
+                let new_value_bind = bind_value(f, quote! { new_value });
+                let value_bind = bind_value(f, quote! { value });
+                let column_name = quote_ident(&field_name.to_string());
+                
+              let lazy_loading_methods = if is_field_type(&f.ty, "Relation") {
+                let relation_type = extract_relation_generic_type(&f.ty).unwrap();
+                let relation_id_column = quote_ident("id");
+
+                    quote!{
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #load_relation_fn_name<'a>(self, executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>) -> sqlx::Result<Option<#relation_type>> {
+                            let query = format!("SELECT {} FROM {} WHERE {} = $1", <#relation_type as ::leviosa::Table>::COLUMNS, <#relation_type as ::leviosa::Table>::TABLE, #relation_id_column);
+                            let mut connection = executor.acquire().await?;
+                            sqlx::query_as::<_, #relation_type>(&query)
+                            .bind(self.#field_name)
+                            .fetch_optional(&mut *connection).await
+
+                        }
+                    }
+                   
+                } else {
+                    quote!{}
+                };
+             
+                let getter_method = if args.getters {
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #get_fn_name<'a>(executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>, value: &#ty) -> sqlx::Result<Option<Self>> {
+
+                            let query = format!("SELECT {} FROM {} WHERE {} = $1{}", #select_list, #table_name, #column_name, #cast);
+                            let mut connection = executor.acquire().await?;
+                            sqlx::query_as::<_, Self>(&query)
+                                .bind(#value_bind)
+                                .fetch_optional(&mut *connection).await
+                        }
+                    }
+                } else {
+                    quote!{}
+                };
+
+                // Blobs are set from borrowed bytes, the copy kept on the struct is the only one made.
+                let (setter_param, new_value_owned) = if is_bytes(ty) {
+                    (quote!{ &[u8] }, quote!{ new_value.to_vec() })
+                } else if is_optional_bytes(ty) {
+                    (quote!{ Option<&[u8]> }, quote!{ new_value.map(<[u8]>::to_vec) })
+                } else {
+                    (quote!{ &#ty }, quote!{ new_value.clone() })
+                };
+
+                // #[unique] columns can be checked without fetching the row.
+                let exists_method = if has_attribute(f, "unique") {
+                    let exists_fn_name = format_ident!("exists_by_{}", field_name);
+                    let exists_str = format!(
+                        "SELECT EXISTS(SELECT 1 FROM {} WHERE {} = $1{})",
+                        table_name, column_name, cast
+                    );
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #exists_fn_name<'a>(
+                            executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                            value: &#ty,
+                        ) -> sqlx::Result<bool> {
+                            let mut connection = executor.acquire().await?;
+                            sqlx::query_scalar::<_, bool>(#exists_str)
+                                .bind(#value_bind)
+                                .fetch_one(&mut *connection)
+                                .await
+                        }
+                    }
+                } else {
+                    quote!{}
+                };
+
+                // #[indexed] columns hold repeated values, find_by_ returns every matching row.
+                let find_by_method = if has_attribute(f, "indexed") {
+                    let find_fn_name = format_ident!("find_by_{}", field_name);
+                    let find_str = format!(
+                        "SELECT {} FROM {} WHERE {} = $1{} ORDER BY {}",
+                        select_list, table_name, column_name, cast, id_column
+                    );
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #find_fn_name<'a>(
+                            executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                            value: &#ty,
+                        ) -> sqlx::Result<Vec<Self>> {
+                            let mut connection = executor.acquire().await?;
+                            sqlx::query_as::<_, Self>(#find_str)
+                                .bind(#value_bind)
+                                .fetch_all(&mut *connection)
+                                .await
+                        }
+                    }
+                } else {
+                    quote!{}
+                };
+
+                let setter_method = if args.view {
+                    quote!{}
+                } else {
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #update_fn_name(&mut self, pool: &sqlx::PgPool, new_value: #setter_param) -> sqlx::Result<()> {
+                            let query = format!("UPDATE {} SET {} = $2{} WHERE {} = $1", #table_name, #column_name, #cast, #id_column);
+                            let result = sqlx::query(&query)
+                                .bind(&self.#primary_key_ident)
+                                .bind(#new_value_bind)
+                                .execute(pool).await?;
+                            if result.rows_affected() == 0 {
+                                return Err(sqlx::Error::RowNotFound);
+                            }
+                            self.#field_name = #new_value_owned;
+                            Ok(())
+                        }
+                    }
+                };
+
+                // Same as the setter, returning the value stored before the update. The row is
+                // locked in a subquery so the old value is the one that was overwritten. Columns
+                // converted on read are left out, their stored value isn't the field's type.
+                let options = column_options(f);
+                let read_as_is = select_expression(f, args.quote_identifiers) == column_name
+                    && options.try_from.is_none()
+                    && options.encrypt_with.is_none()
+                    && !options.intern;
+                let returning_old_method = if !args.view && read_as_is && *field_name != primary_key_ident {
+                    let returning_old_fn_name = format_ident!("{}_{}_returning_old", args.setter_prefix, field_name);
+                    let returning_old_str = format!(
+                        "UPDATE {table} SET {column} = $2{cast} FROM (SELECT {id}, {column} FROM {table} WHERE {id} = $1 FOR UPDATE) AS __leviosa_old WHERE {table}.{id} = __leviosa_old.{id} RETURNING __leviosa_old.{column}",
+                        table = table_name,
+                        column = column_name,
+                        cast = cast,
+                        id = id_column
+                    );
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #returning_old_fn_name(&mut self, pool: &sqlx::PgPool, new_value: #setter_param) -> sqlx::Result<#ty> {
+                            let old_value = sqlx::query_scalar::<_, #ty>(#returning_old_str)
+                                .bind(&self.#primary_key_ident)
+                                .bind(#new_value_bind)
+                                .fetch_optional(pool).await?
+                                .ok_or(sqlx::Error::RowNotFound)?;
+                            self.#field_name = #new_value_owned;
+                            Ok(old_value)
+                        }
+                    }
+                } else {
+                    quote!{}
+                };
+
+                // Columns marked jsonb can take a patch merged in with ||, keys of the patch
+                // replace those already stored and the others are kept.
+                let jsonb_column = column_options(f).sql_type.as_deref() == Some("jsonb");
+                let merge_method = if !args.view && is_json_value(ty) && jsonb_column {
+                    let merge_fn_name = format_ident!("merge_{}", field_name);
+                    let merge_str = format!(
+                        "UPDATE {} SET {} = COALESCE({}, '{{}}'::jsonb) || $2 WHERE {} = $1 RETURNING {}",
+                        table_name, column_name, column_name, id_column, column_name
+                    );
+                    let merged_value = if is_field_type(ty, "Option") {
+                        quote!{ Some(merged) }
+                    } else {
+                        quote!{ merged }
+                    };
+                    quote! {
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn #merge_fn_name(&mut self, pool: &sqlx::PgPool, patch: ::leviosa::serde_json::Value) -> sqlx::Result<()> {
+                            let merged = sqlx::query_scalar::<_, ::leviosa::serde_json::Value>(#merge_str)
+                                .bind(&self.#primary_key_ident)
+                                .bind(patch)
+                                .fetch_optional(pool).await?
+                                .ok_or(sqlx::Error::RowNotFound)?;
+                            self.#field_name = #merged_value;
+                            Ok(())
+                        }
+                    }
+                } else {
+                    quote!{}
+                };
+
+                // Generate get_by_ and update_ methods
+                quote! {
+                    #getter_method
+                    #exists_method
+                    #find_by_method
+                    #setter_method
+                    #returning_old_method
+                    #merge_method
+                    #lazy_loading_methods
+                }
+            }).collect(),
+            _ => quote! {},
+        }
+    } else {
+        quote! {}
+    };
+
+    let (create_method, insert_impl) = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let field_params = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f) && !generates_uuid_v4(f))
+                    .map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        let ty = &f.ty;
+                        // Text is bound as the borrowed `&str`, so a caller passing a literal or
+                        // a borrowed String doesn't allocate. Bytes take any byte slice.
+                        if is_string(ty) {
+                            quote! { #field_name: impl AsRef<str> }
+                        } else if is_bytes(ty) {
+                            quote! { #field_name: impl AsRef<[u8]> }
+                        } else if is_optional_bytes(ty) {
+                            quote! { #field_name: Option<&[u8]> }
+                        } else {
+                            quote! { #field_name: #ty }
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let param_names = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f) && !generates_uuid_v4(f))
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+
+                let string_fields = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f) && is_string(&f.ty))
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+
+                let bytes_fields = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f) && is_bytes(&f.ty))
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+
+                let generated_keys = fields
+                    .named
+                    .iter()
+                    .filter(|f| generates_uuid_v4(f))
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+
+                let field_tokens = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f))
+                    .map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        bind_value(f, quote! { #field_name })
+                    })
+                    .collect::<Vec<_>>();
+
+                let insert_fields = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f))
+                    .map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        let value = bind_value(f, quote! { &entity.#field_name });
+                        let cast = placeholder_cast(f);
+                        if cast.is_empty() {
+                            quote! { row.push_bind(#value); }
+                        } else {
+                            quote! {
+                                row.push_bind(#value);
+                                row.push_unseparated(#cast);
+                            }
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let joined_fields = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f))
+                    .map(|f| quote_ident(&f.ident.as_ref().unwrap().to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let values_str: Vec<String> = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f))
+                    .enumerate()
+                    .map(|(i, f)| format!("${}{}", i + 1, placeholder_cast(f)))
+                    .collect();
+                let values_str = values_str.join(", ");
+
+                let query_str = format!(
+                    "INSERT INTO {} ({}) VALUES ({}) RETURNING {}",
+                    table_name, joined_fields, values_str, select_list
+                );
+                let returning_columns_str = format!(" RETURNING {}", select_list);
+
+                let insert_many_str = format!("INSERT INTO {} ({}) ", table_name, joined_fields);
+
+                // Postgres takes at most 65535 bind parameters per statement, longer batches are
+                // split into statements of this many rows run in one transaction.
+                let inserted_count = fields.named.iter().filter(|f| is_inserted(f)).count();
+                let rows_per_statement = (u16::MAX as usize / inserted_count.max(1)).max(1);
+
+                // #[leviosa(hooks)]: every insert runs the hooks. The batch inserts copy the
+                // entities so before_create can change them, the bound only fails a call on
+                // structs that aren't Clone.
+                let (clone_bound, clone_where) = if args.hooks {
+                    (
+                        quote! { for<'__leviosa> #name: Clone, },
+                        quote! { where for<'__leviosa> #name: Clone },
+                    )
+                } else {
+                    (quote! {}, quote! {})
+                };
+
+                // Checked at the top of standard_methods.
+                let primary_key = primary_key_field(fields).unwrap();
+                let id_ty = primary_key_type(primary_key);
+                let returning_str = format!(
+                    " RETURNING {}",
+                    quote_ident(&primary_key.ident.as_ref().unwrap().to_string())
+                );
+                let create_returning_id_str = format!(
+                    "INSERT INTO {} ({}) VALUES ({}){}",
+                    table_name, joined_fields, values_str, returning_str
+                );
+                let key_name = primary_key.ident.as_ref().unwrap();
+                let primary_key_value = if is_field_type(&primary_key.ty, "AutoGenerated") {
+                    quote! { #key_name.0 }
+                } else {
+                    quote! { #key_name }
+                };
+                // With hooks the key is read from the entity after_create was given.
+                let (create_returning_id_body, create_many_ids_body) = if args.hooks {
+                    (
+                        quote! {
+                            let created = Self::create(pool, #(#param_names),*).await?;
+                            Ok(created.#primary_key_value)
+                        },
+                        quote! {
+                            let created = Self::create_many(pool, entities).await?;
+                            Ok(created
+                                .into_iter()
+                                .map(|entity| entity.#primary_key_value)
+                                .collect())
+                        },
+                    )
+                } else {
+                    (
+                        quote! {
+                            #( let #string_fields: &str = #string_fields.as_ref(); )*
+                            #( let #bytes_fields: &[u8] = #bytes_fields.as_ref(); )*
+                            #( let #generated_keys = ::leviosa::uuid_v4(); )*
+                            sqlx::query_scalar::<_, #id_ty>(#create_returning_id_str)
+                                #( .bind(#field_tokens) )*
+                                .fetch_one(pool)
+                                .await
+                        },
+                        quote! {
+                            if entities.len() <= #rows_per_statement {
+                                if entities.is_empty() {
+                                    return Ok(Vec::new());
+                                }
+                                let mut query_builder = Self::create_many_query(entities);
+                                query_builder.push(#returning_str);
+                                return query_builder
+                                    .build_query_scalar::<#id_ty>()
+                                    .fetch_all(pool)
+                                    .await;
+                            }
+                            let mut transaction = pool.begin().await?;
+                            let mut ids = Vec::with_capacity(entities.len());
+                            for chunk in entities.chunks(#rows_per_statement) {
+                                let mut query_builder = Self::create_many_query(chunk);
+                                query_builder.push(#returning_str);
+                                ids.extend(
+                                    query_builder
+                                        .build_query_scalar::<#id_ty>()
+                                        .fetch_all(&mut *transaction)
+                                        .await?,
+                                );
+                            }
+                            transaction.commit().await?;
+                            Ok(ids)
+                        },
+                    )
+                };
+                let create_ids_methods = quote! {
+                    // Same as create, but only the generated key is sent back.
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn create_returning_id(
+                        pool: &sqlx::PgPool,
+                        #(#field_params),*
+                    ) -> Result<#id_ty, sqlx::Error> {
+                        #create_returning_id_body
+                    }
+
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn create_many_ids(
+                        pool: &sqlx::PgPool,
+                        entities: &[Self],
+                    ) -> Result<Vec<#id_ty>, sqlx::Error>
+                    #clone_where
+                    {
+                        #create_many_ids_body
+                    }
+                };
+
+                let upsert_columns = fields
+                    .named
+                    .iter()
+                    .filter(|f| is_inserted(f) && *f.ident.as_ref().unwrap() != primary_key_ident)
+                    .map(|f| quote_ident(&f.ident.as_ref().unwrap().to_string()));
+
+                let insert_impl = quote! {
+                    impl ::leviosa::Insert for #name {
+                        const INSERT: &'static str = #insert_many_str;
+                        const UPSERT_COLUMNS: &'static [&'static str] = &[#(#upsert_columns),*];
+
+                        fn push_values<'q>(
+                            &'q self,
+                            query_builder: &mut sqlx::QueryBuilder<'q, sqlx::Postgres>,
+                        ) {
+                            query_builder.push_values(::std::iter::once(self), |mut row, entity| {
+                                #( #insert_fields )*
+                            });
+                        }
+                    }
+                };
+
+                // #[leviosa(hooks)]: the entity is built first so before_create can change it,
+                // fields the database fills in start out as their Default.
+                let create_body = if args.hooks {
+                    let entity_values = fields.named.iter().map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        if is_skipped(f) || column_options(f).db_generated {
+                            quote! { ::std::default::Default::default() }
+                        } else if is_field_type(&f.ty, "AutoGenerated") {
+                            quote! { AutoGenerated(::std::default::Default::default()) }
+                        } else if is_string(&f.ty) {
+                            quote! { #field_name.to_owned() }
+                        } else if is_bytes(&f.ty) {
+                            quote! { #field_name.to_vec() }
+                        } else if is_optional_bytes(&f.ty) {
+                            quote! { #field_name.map(<[u8]>::to_vec) }
+                        } else {
+                            quote! { #field_name }
+                        }
+                    });
+                    quote! {
+                        let mut entity = Self::new(#(#entity_values),*);
+                        ::leviosa::Hooks::before_create(&mut entity);
+                        let created = Self::insert_many(pool, ::std::slice::from_ref(&entity))
+                            .await?
+                            .pop()
+                            .ok_or(sqlx::Error::RowNotFound)?;
+                        ::leviosa::Hooks::after_create(&created, pool).await?;
+                        Ok(created)
+                    }
+                } else {
+                    quote! {
+                        let new_entity = sqlx::query_as::<_, Self>(&#query_str)
+                            #( .bind(#field_tokens) )*
+                            .fetch_one(pool) // Execute query within the transaction
+                            .await?;
+                        Ok(new_entity)
+                    }
+                };
+
+                let create_many_body = if args.hooks {
+                    quote! {
+                        let mut entities = entities.to_vec();
+                        for entity in &mut entities {
+                            ::leviosa::Hooks::before_create(entity);
+                        }
+                        let created = Self::insert_many(pool, &entities).await?;
+                        for entity in &created {
+                            ::leviosa::Hooks::after_create(entity, pool).await?;
+                        }
+                        Ok(created)
+                    }
+                } else {
+                    quote! {
+                        Self::insert_many(pool, entities).await
+                    }
+                };
+
+                // These insert from a borrowed entity or a prepared statement without running
+                // the hooks, so hooks structs don't get them.
+                let unhooked_insert_methods = if args.hooks {
+                    quote! {}
+                } else {
+                    quote! {
+                        // Inserts rows one by one on the connection, reusing one prepared statement.
+                        pub fn prepared_create(
+                            connection: &mut sqlx::PgConnection,
+                        ) -> ::leviosa::PreparedInsert<'_, Self> {
+                            ::leviosa::PreparedInsert::new(connection)
+                        }
+
+                        // Inserts unless the row conflicts with an existing one, see `on_conflict`.
+                        pub fn insert_or_ignore(&self) -> ::leviosa::InsertQueryBuilder<'_, Self> {
+                            ::leviosa::InsertQueryBuilder::new(self)
+                        }
+
+                        // Inserts, or overwrites the row it conflicts with, see `update_only`.
+                        pub fn upsert(&self) -> ::leviosa::InsertQueryBuilder<'_, Self> {
+                            ::leviosa::InsertQueryBuilder::upsert(self)
+                        }
+                    }
+                };
+
+                let create_method = quote! {
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn create(
+                        pool: &sqlx::PgPool,
+                        #(#field_params),*
+                    ) -> Result<Self, sqlx::Error> {
+                        #( let #string_fields: &str = #string_fields.as_ref(); )*
+                        #( let #bytes_fields: &[u8] = #bytes_fields.as_ref(); )*
+                        #( let #generated_keys = ::leviosa::uuid_v4(); )*
+                        #create_body
+                    }
+
+                    // Rows come back in the order they were given. Batches too large for one
+                    // statement are split, all or none of the rows are inserted.
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn create_many(
+                        pool: &sqlx::PgPool,
+                        entities: &[Self],
+                    ) -> Result<Vec<Self>, sqlx::Error>
+                    #clone_where
+                    {
+                        #create_many_body
+                    }
+
+                    async fn insert_many(
+                        pool: &sqlx::PgPool,
+                        entities: &[Self],
+                    ) -> Result<Vec<Self>, sqlx::Error> {
+                        if entities.len() <= #rows_per_statement {
+                            if entities.is_empty() {
+                                return Ok(Vec::new());
+                            }
+                            let mut query_builder = Self::create_many_query(entities);
+                            query_builder.push(#returning_columns_str);
+                            return query_builder.build_query_as::<Self>().fetch_all(pool).await;
+                        }
+                        let mut transaction = pool.begin().await?;
+                        let mut created = Vec::with_capacity(entities.len());
+                        for chunk in entities.chunks(#rows_per_statement) {
+                            let mut query_builder = Self::create_many_query(chunk);
+                            query_builder.push(#returning_columns_str);
+                            created.extend(
+                                query_builder
+                                    .build_query_as::<Self>()
+                                    .fetch_all(&mut *transaction)
+                                    .await?,
+                            );
+                        }
+                        transaction.commit().await?;
+                        Ok(created)
+                    }
+
+                    #create_ids_methods
+
+                    // Inserts rows from a stream, batch_size rows per statement, yielding the
+                    // inserted rows in order.
+                    #[must_use = "streams do nothing unless polled"]
+                    pub fn insert_returning<'a, S>(
+                        pool: &'a sqlx::PgPool,
+                        entities: S,
+                    ) -> impl ::leviosa::Stream<Item = sqlx::Result<Self>> + 'a
+                    where
+                        S: ::leviosa::Stream<Item = Self> + 'a,
+                        #clone_bound
+                    {
+                        Self::insert_returning_batched(pool, entities, ::leviosa::DEFAULT_BATCH_SIZE)
+                    }
+
+                    #[must_use = "streams do nothing unless polled"]
+                    pub fn insert_returning_batched<'a, S>(
+                        pool: &'a sqlx::PgPool,
+                        entities: S,
+                        batch_size: usize,
+                    ) -> impl ::leviosa::Stream<Item = sqlx::Result<Self>> + 'a
+                    where
+                        S: ::leviosa::Stream<Item = Self> + 'a,
+                        #clone_bound
+                    {
+                        ::leviosa::insert_batches(entities, batch_size, move |batch: Vec<Self>| async move {
+                            Self::create_many(pool, &batch).await
+                        })
+                    }
+
+                    fn create_many_query(entities: &[Self]) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
+                        let mut query_builder = sqlx::QueryBuilder::new(#insert_many_str);
+                        query_builder.push_values(entities, |mut row, entity| {
+                            #( #insert_fields )*
+                        });
+                        query_builder
+                    }
+
+                    #unhooked_insert_methods
+                };
+
+                (create_method, insert_impl)
+            }
+            _ => (quote! {}, quote! {}),
+        }
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let find_all_query_builder_name = format_ident!(
+        "{}FindAllQueryBuilder",
+        input.ident.to_string().to_camel_case()
+    );
+
+    let delete_all_query_builder_name = format_ident!(
+        "{}DeleteAllQueryBuilder",
+        input.ident.to_string().to_camel_case()
+    );
+
+    let find_all_query_builder = quote! {
+        type #find_all_query_builder_name = ::leviosa::FindAllQueryBuilder<#name>;
+    };
+
+    let table_impl = quote! {
+        impl ::leviosa::Table for #name {
+            const TABLE: &'static str = #table_name;
+            const COLUMNS: &'static str = #select_list;
+        }
+    };
+
+    // Implemented on PgPool so generic code can take any pool as `&impl Repository<Entity>`.
+    let repository_impl = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                // Checked at the top of standard_methods.
+                let primary_key = primary_key_field(fields).unwrap();
+                let id_ty = primary_key_type(primary_key);
+                let get_str = format!(
+                    "SELECT {} FROM {} WHERE {} = $1",
+                    select_list, table_name, id_column
+                );
+                let delete_str = format!("DELETE FROM {} WHERE {} = $1", table_name, id_column);
+                let all_str = format!("SELECT {} FROM {}", select_list, table_name);
+                // create runs the hooks through create_many, which needs the struct Clone.
+                let repository_where = if args.hooks {
+                    quote! { where for<'__leviosa> #name: Clone }
+                } else {
+                    quote! {}
+                };
+                quote! {
+                    impl ::leviosa::Repository<#name> for sqlx::PgPool #repository_where {
+                        type Id = #id_ty;
+
+                        async fn create(&self, entity: &#name) -> sqlx::Result<#name> {
+                            let mut created =
+                                #name::create_many(self, ::std::slice::from_ref(entity)).await?;
+                            created.pop().ok_or(sqlx::Error::RowNotFound)
+                        }
+
+                        async fn get_by_id(&self, id: &#id_ty) -> sqlx::Result<Option<#name>> {
+                            sqlx::query_as::<_, #name>(#get_str)
+                                .bind(id)
+                                .fetch_optional(self)
+                                .await
+                        }
+
+                        async fn delete(&self, id: &#id_ty) -> sqlx::Result<bool> {
+                            let result = sqlx::query(#delete_str).bind(id).execute(self).await?;
+                            Ok(result.rows_affected() > 0)
+                        }
+
+                        async fn all(&self) -> sqlx::Result<Vec<#name>> {
+                            sqlx::query_as::<_, #name>(#all_str).fetch_all(self).await
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    let delete_all_query_builder = quote! {
+        type #delete_all_query_builder_name = ::leviosa::DeleteAllQueryBuilder<#name>;
+    };
+
+    // Define the find_all method for the struct
+    let count_all_str = format!("SELECT COUNT(*) FROM {}", table_name);
+
+    let select_all_str = format!("SELECT {} FROM {}", select_list, table_name);
+
+    let find_all_method = quote! {
+        #[must_use = "builders do nothing unless executed"]
+        pub fn find() -> #find_all_query_builder_name {
+            ::leviosa::FindAllQueryBuilder::new()
+        }
+
+        // A plain sqlx QueryBuilder holding `SELECT <columns> FROM <table>`, for queries the
+        // builders can't express. The rows read back as Self.
+        #[must_use = "builders do nothing unless executed"]
+        pub fn query<'q>() -> sqlx::QueryBuilder<'q, sqlx::Postgres> {
+            sqlx::QueryBuilder::new(#select_all_str)
+        }
+
+        // Counts every row of the table.
+        #[must_use = "the result of the query should be handled"]
+        pub async fn count_all<'a>(
+            executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+        ) -> sqlx::Result<i64> {
+            let mut connection = executor.acquire().await?;
+            sqlx::query_scalar::<_, i64>(#count_all_str)
+                .fetch_one(&mut *connection)
+                .await
+        }
+    };
+
+    let truncate_str = format!("TRUNCATE TABLE {} RESTART IDENTITY", table_name);
+    let truncate_cascade_str = format!("{} CASCADE", truncate_str);
+
+    let delete_all_method = quote! {
+        #[must_use = "builders do nothing unless executed"]
+        pub fn delete_all() -> #delete_all_query_builder_name {
+            ::leviosa::DeleteAllQueryBuilder::new()
+        }
+
+        #[must_use = "builders do nothing unless executed"]
+        pub fn delete_where() -> #delete_all_query_builder_name {
+            ::leviosa::DeleteAllQueryBuilder::new()
+        }
+
+        // Empties the table and restarts its id sequence.
+        #[must_use = "the result of the query should be handled"]
+        pub async fn truncate(pool: &sqlx::PgPool) -> sqlx::Result<()> {
+            sqlx::query(#truncate_str).execute(pool).await?;
+            Ok(())
+        }
+
+        // Same as truncate, also emptying tables with foreign keys to this one.
+        #[must_use = "the result of the query should be handled"]
+        pub async fn truncate_cascade(pool: &sqlx::PgPool) -> sqlx::Result<()> {
+            sqlx::query(#truncate_cascade_str).execute(pool).await?;
+            Ok(())
+        }
+    };
+
+    let constructor = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let field_params = fields.named.iter().map(|f| {
+                    let field_name = f.ident.as_ref().unwrap();
+                    let ty = &f.ty;
+                    quote! { #field_name: #ty }
+                });
+
+                // Extract field names for struct initialization
+                let field_names = fields.named.iter().map(|f| {
+                    let field_name = f.ident.as_ref().unwrap();
+                    quote! { #field_name: #field_name }
+                });
+                quote! {
+                    pub fn new(#(#field_params),*) -> Self {
+                        Self {
+                            #(#field_names,)*
+                            #( #cache_fields: ::std::default::Default::default(), )*
+                        }
+                    }
+                }
+            }
+            _ => {
+                quote! {}
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Compares persisted columns only, skipped fields are ignored. The higher ranked bounds keep
+    // the method from failing to compile for structs whose columns are not PartialEq.
+    let same_as_method = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let columns = fields.named.iter().filter(|f| !is_skipped(f));
+                // AutoGenerated values are compared on the value they wrap.
+                let column_values = columns.clone().map(|f| {
+                    let field_name = f.ident.as_ref().unwrap();
+                    if is_field_type(&f.ty, "AutoGenerated") {
+                        quote! { #field_name.0 }
+                    } else {
+                        quote! { #field_name }
+                    }
+                });
+                let column_types =
+                    columns.map(|f| extract_generic_type(&f.ty, "AutoGenerated").unwrap_or(&f.ty));
+                quote! {
+                    pub fn same_as(&self, other: &Self) -> bool
+                    where
+                        #(for<'__leviosa> #column_types: PartialEq),*
+                    {
+                        true #( && self.#column_values == other.#column_values )*
+                    }
+                }
+            }
+            _ => quote! {},
+        }
+    } else {
+        quote! {}
+    };
+
+    // The row as stored, keyed by column, for callers whose serde impl is the API view. Bounded
+    // like same_as so structs with a column that isn't Serialize still compile.
+    let to_row_json_method = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let columns = fields.named.iter().filter(|f| !is_skipped(f));
+                let column_names = columns
+                    .clone()
+                    .map(|f| f.ident.as_ref().unwrap().to_string());
+                let column_values = columns.clone().map(|f| {
+                    let field_name = f.ident.as_ref().unwrap();
+                    if is_field_type(&f.ty, "AutoGenerated") {
+                        quote! { #field_name.0 }
+                    } else {
+                        quote! { #field_name }
+                    }
+                });
+                let column_types =
+                    columns.map(|f| extract_generic_type(&f.ty, "AutoGenerated").unwrap_or(&f.ty));
+                quote! {
+                    pub fn to_row_json(&self) -> ::leviosa::serde_json::Result<::leviosa::serde_json::Value>
+                    where
+                        #(for<'__leviosa> #column_types: ::leviosa::serde::Serialize),*
+                    {
+                        let mut row = ::leviosa::serde_json::Map::new();
+                        #(
+                            row.insert(
+                                String::from(#column_names),
+                                ::leviosa::serde_json::to_value(&self.#column_values)?,
+                            );
+                        )*
+                        Ok(::leviosa::serde_json::Value::Object(row))
+                    }
+                }
+            }
+            _ => quote! {},
+        }
+    } else {
+        quote! {}
+    };
+
+    // One ANY($1) query, the rows are then lined up with the ids they were asked for.
+    let get_by_ids_method = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.getters => {
+                // Checked at the top of standard_methods.
+                let primary_key = primary_key_field(fields).unwrap();
+                let id_ty = primary_key_type(primary_key);
+                let id_value = if is_field_type(&primary_key.ty, "AutoGenerated") {
+                    quote! { row.#primary_key_ident.0 }
+                } else {
+                    quote! { row.#primary_key_ident.clone() }
+                };
+                let query_str = format!(
+                    "SELECT {} FROM {} WHERE {} = ANY($1)",
+                    select_list, table_name, id_column
+                );
+                quote! {
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn get_by_ids<'a>(
+                        executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                        ids: &[#id_ty],
+                    ) -> sqlx::Result<Vec<Option<Self>>>
+                    where
+                        for<'__leviosa> Self: Clone,
+                        for<'__leviosa> #id_ty: ::std::hash::Hash + Eq,
+                    {
+                        let mut connection = executor.acquire().await?;
+                        let rows = sqlx::query_as::<_, Self>(#query_str)
+                            .bind(ids)
+                            .fetch_all(&mut *connection)
+                            .await?;
+                        let rows: ::std::collections::HashMap<#id_ty, Self> = rows
+                            .into_iter()
+                            .map(|row| (#id_value, row))
+                            .collect();
+                        Ok(ids.iter().map(|id| rows.get(id).cloned()).collect())
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    let update_query_builder_name = format_ident!(
+        "{}UpdateQueryBuilder",
+        input.ident.to_string().to_camel_case()
+    );
+
+    // Every setter stores Option<T>, None meaning the column is left alone. For nullable
+    // columns T is itself an Option so passing None writes NULL.
+    let update_query_builder = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let columns = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated") && !is_skipped(f))
+                    .filter(|f| *f.ident.as_ref().unwrap() != primary_key_ident);
+                let column_names: Vec<_> =
+                    columns.clone().map(|f| f.ident.as_ref().unwrap()).collect();
+                let column_types: Vec<_> = columns.clone().map(|f| &f.ty).collect();
+                let assignments: Vec<_> = column_names
+                    .iter()
+                    .map(|field_name| format!("{} = ", quote_ident(&field_name.to_string())))
+                    .collect();
+                let binds: Vec<_> = columns
+                    .clone()
+                    .map(|f| bind_value(f, quote! { value }))
+                    .collect();
+                let casts: Vec<_> = columns.map(placeholder_cast).collect();
+                let update_str = format!("UPDATE {} SET ", table_name);
+                let where_id_str = format!(" WHERE {} = ", id_column);
+
+                quote! {
+                    #[must_use = "builders do nothing unless executed"]
+                    struct #update_query_builder_name<'a> {
+                        entity: &'a mut #name,
+                        #( #column_names: Option<#column_types> ),*
+                    }
+
+                    impl<'a> #update_query_builder_name<'a> {
+                        fn new(entity: &'a mut #name) -> Self {
+                            Self {
+                                entity,
+                                #( #column_names: None ),*
+                            }
+                        }
+
+                        #(
+                            fn #column_names(&mut self, value: #column_types) -> &mut Self {
+                                self.#column_names = Some(value);
+                                self
+                            }
+                        )*
+
+                        pub async fn execute(&mut self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
+                            {
+                                let mut query_builder = sqlx::QueryBuilder::<sqlx::Postgres>::new(#update_str);
+                                let mut changed = false;
+                                {
+                                    let mut separated = query_builder.separated(", ");
+                                    #(
+                                        if let Some(ref value) = self.#column_names {
+                                            separated.push(#assignments);
+                                            separated.push_bind_unseparated(#binds);
+                                            separated.push_unseparated(#casts);
+                                            changed = true;
+                                        }
+                                    )*
+                                }
+
+                                if !changed {
+                                    return Ok(());
+                                }
+
+                                query_builder.push(#where_id_str);
+                                query_builder.push_bind(&self.entity.#primary_key_ident);
+                                let result = query_builder.build().execute(pool).await?;
+                                if result.rows_affected() == 0 {
+                                    return Err(sqlx::Error::RowNotFound);
+                                }
+                            }
+
+                            #(
+                                if let Some(value) = self.#column_names.take() {
+                                    self.entity.#column_names = value;
+                                }
+                            )*
+                            Ok(())
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        }
+    } else {
+        quote! {}
+    };
+
+    let update_method = quote! {
+        #[must_use = "builders do nothing unless executed"]
+        pub fn update(&mut self) -> #update_query_builder_name<'_> {
+            #update_query_builder_name::new(self)
+        }
+    };
+
+    // Writes every column update() can write from each entity in one UPDATE joined against a
+    // VALUES list on the primary key, split like create_many when there are too many binds.
+    let update_rows_method = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let columns = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated") && !is_skipped(f))
+                    .filter(|f| *f.ident.as_ref().unwrap() != primary_key_ident)
+                    .collect::<Vec<_>>();
+                if columns.is_empty() {
+                    quote! {}
+                } else {
+                    let column_strs = columns
+                        .iter()
+                        .map(|f| quote_ident(&f.ident.as_ref().unwrap().to_string()))
+                        .collect::<Vec<_>>();
+                    let assignments = column_strs
+                        .iter()
+                        .map(|column| format!("{} = __leviosa_rows.{}", column, column))
+                        .collect::<Vec<_>>();
+                    let update_rows_str = format!(
+                        "UPDATE {} SET {} FROM (",
+                        table_name,
+                        assignments.join(", ")
+                    );
+                    let join_str = format!(
+                        ") AS __leviosa_rows ({}, {}) WHERE {}.{} = __leviosa_rows.{}",
+                        id_column,
+                        column_strs.join(", "),
+                        table_name,
+                        id_column,
+                        id_column
+                    );
+                    let row_values = columns.iter().map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        let value = bind_value(f, quote! { &entity.#field_name });
+                        let cast = placeholder_cast(f);
+                        if cast.is_empty() {
+                            quote! { row.push_bind(#value); }
+                        } else {
+                            quote! {
+                                row.push_bind(#value);
+                                row.push_unseparated(#cast);
+                            }
+                        }
+                    });
+                    let rows_per_statement = (u16::MAX as usize / (columns.len() + 1)).max(1);
+                    quote! {
+                        // Returns how many rows were updated, rows whose key is gone are skipped.
+                        // All or none of the rows are written.
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn update_rows(
+                            pool: &sqlx::PgPool,
+                            entities: &[Self],
+                        ) -> sqlx::Result<u64> {
+                            if entities.len() <= #rows_per_statement {
+                                if entities.is_empty() {
+                                    return Ok(0);
+                                }
+                                let result = Self::update_rows_query(entities)
+                                    .build()
+                                    .execute(pool)
+                                    .await?;
+                                return Ok(result.rows_affected());
+                            }
+                            let mut transaction = pool.begin().await?;
+                            let mut updated = 0;
+                            for chunk in entities.chunks(#rows_per_statement) {
+                                updated += Self::update_rows_query(chunk)
+                                    .build()
+                                    .execute(&mut *transaction)
+                                    .await?
+                                    .rows_affected();
+                            }
+                            transaction.commit().await?;
+                            Ok(updated)
+                        }
+
+                        fn update_rows_query(entities: &[Self]) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
+                            let mut query_builder = sqlx::QueryBuilder::new(#update_rows_str);
+                            query_builder.push_values(entities, |mut row, entity| {
+                                row.push_bind(&entity.#primary_key_ident);
+                                #( #row_values )*
+                            });
+                            query_builder.push(#join_str);
+                            query_builder
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    // Compares the columns update() can write against a modified copy, so only what changed is
+    // sent. Bounded like same_as so structs whose columns aren't PartialEq still compile.
+    let change_tracking_methods = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let columns = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated") && !is_skipped(f))
+                    .filter(|f| *f.ident.as_ref().unwrap() != primary_key_ident);
+                let column_names: Vec<_> =
+                    columns.clone().map(|f| f.ident.as_ref().unwrap()).collect();
+                let column_types: Vec<_> = columns.map(|f| &f.ty).collect();
+                let column_strs = column_names
+                    .iter()
+                    .map(|field_name| quote_ident(&field_name.to_string()));
+                quote! {
+                    // The columns whose values differ between the two, in field order.
+                    pub fn changed_columns(&self, other: &Self) -> Vec<&'static str>
+                    where
+                        #(for<'__leviosa> #column_types: PartialEq),*
+                    {
+                        let mut changed = Vec::new();
+                        #(
+                            if self.#column_names != other.#column_names {
+                                changed.push(#column_strs);
+                            }
+                        )*
+                        changed
+                    }
+
+                    // Writes the columns `modified` changed in one UPDATE and takes their values.
+                    // Nothing is sent when no column changed.
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn update_changed(
+                        &mut self,
+                        pool: &sqlx::PgPool,
+                        modified: &Self,
+                    ) -> sqlx::Result<()>
+                    where
+                        #(for<'__leviosa> #column_types: PartialEq + Clone),*
+                    {
+                        let mut update = #update_query_builder_name::new(self);
+                        #(
+                            if update.entity.#column_names != modified.#column_names {
+                                update.#column_names(modified.#column_names.clone());
+                            }
+                        )*
+                        update.execute(pool).await
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    // #[leviosa(changeset)] generates <Struct>Changeset, an optional value per column update()
+    // can write, and apply() sends the ones that are set in one UPDATE.
+    let (changeset_struct, changeset_method) = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.changeset => {
+                let changeset_name = format_ident!("{}Changeset", name);
+                let vis = &input.vis;
+                let columns = fields
+                    .named
+                    .iter()
+                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated") && !is_skipped(f))
+                    .filter(|f| *f.ident.as_ref().unwrap() != primary_key_ident);
+                let column_names: Vec<_> =
+                    columns.clone().map(|f| f.ident.as_ref().unwrap()).collect();
+                let column_types: Vec<_> = columns.map(|f| &f.ty).collect();
+                (
+                    quote! {
+                        #[derive(Debug, Clone, Default, ::leviosa::serde::Deserialize)]
+                        #[serde(crate = "::leviosa::serde")]
+                        #vis struct #changeset_name {
+                            #( pub #column_names: Option<#column_types>, )*
+                        }
+                    },
+                    quote! {
+                        // Writes the fields of `changeset` that are Some in one UPDATE and takes
+                        // their values. Nothing is sent when none is set.
+                        #[must_use = "the result of the query should be handled"]
+                        pub async fn apply(
+                            &mut self,
+                            changeset: #changeset_name,
+                            pool: &sqlx::PgPool,
+                        ) -> sqlx::Result<()> {
+                            let mut update = #update_query_builder_name::new(self);
+                            #(
+                                if let Some(value) = changeset.#column_names {
+                                    update.#column_names(value);
+                                }
+                            )*
+                            update.execute(pool).await
+                        }
+                    },
+                )
+            }
+            _ => (quote! {}, quote! {}),
+        },
+        _ => (quote! {}, quote! {}),
+    };
+
+    let cursor_name = format_ident!("{}Cursor", name);
+
+    // #[leviosa(filter)] generates <Struct>Filter, an optional value per column that can be read
+    // from query parameters. Relations, columns with a #[column] type, encrypted, interned,
+    // try_from and bool flag columns are left out, their values don't compare as is.
+    let filter_impl = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.filter => {
+                let filter_name = format_ident!("{}Filter", name);
+                let vis = &input.vis;
+                let filtered = fields
+                    .named
+                    .iter()
+                    .filter(|f| {
+                        let options = column_options(f);
+                        !is_skipped(f)
+                            && !is_field_type(&f.ty, "Relation")
+                            && options.sql_type.is_none()
+                            && options.encrypt_with.is_none()
+                            && !options.intern
+                            && options.try_from.is_none()
+                            && !is_bool_flag(f)
+                    })
+                    .collect::<Vec<_>>();
+                let filter_fields = filtered
+                    .iter()
+                    .map(|f| f.ident.as_ref().unwrap())
+                    .collect::<Vec<_>>();
+                let filter_types = filtered.iter().map(|f| {
+                    extract_generic_type(&f.ty, "AutoGenerated")
+                        .or_else(|| extract_generic_type(&f.ty, "Option"))
+                        .unwrap_or(&f.ty)
+                });
+                let filter_columns = filter_fields
+                    .iter()
+                    .map(|field_name| quote_ident(&field_name.to_string()));
+                quote! {
+                    #[derive(Debug, Clone, Default, ::leviosa::serde::Deserialize)]
+                    #[serde(crate = "::leviosa::serde")]
+                    #vis struct #filter_name {
+                        #( pub #filter_fields: Option<#filter_types>, )*
+                    }
+
+                    impl ::leviosa::Filter<#name> for #filter_name {
+                        fn apply(&self, query: &mut ::leviosa::FindAllQueryBuilder<#name>) {
+                            #(
+                                if let Some(value) = &self.#filter_fields {
+                                    query.where_eq(#filter_columns, value.clone());
+                                }
+                            )*
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    let keyset_impl = if let Data::Struct(data) = &input.data {
+        match &data.fields {
+            Fields::Named(fields) => {
+                let cursor_columns = match &args.cursor {
+                    Some(columns) => columns.clone(),
+                    None => primary_key_field(fields)
+                        .map(|f| vec![f.ident.as_ref().unwrap().to_string()])
+                        .unwrap_or_default(),
+                };
+
+                let mut cursor_fields = Vec::new();
+                for column in &cursor_columns {
+                    match fields
+                        .named
+                        .iter()
+                        .find(|f| f.ident.as_ref().unwrap() == column)
+                    {
+                        Some(field) => cursor_fields.push(field),
+                        None => {
+                            let message =
+                                format!("leviosa: cursor column `{}` is not a field", column);
+                            return syn::Error::new_spanned(name, message)
+                                .to_compile_error()
+                                .into();
+                        }
+                    }
+                }
+
+                if cursor_fields.is_empty() {
+                    quote! {}
+                } else {
+                    let vis = &input.vis;
+                    let cursor_column_names =
+                        cursor_columns.iter().map(|column| quote_ident(column));
+                    let cursor_names: Vec<_> = cursor_fields
+                        .iter()
+                        .map(|f| f.ident.as_ref().unwrap())
+                        .collect();
+                    let cursor_types = cursor_fields
+                        .iter()
+                        .map(|f| extract_generic_type(&f.ty, "AutoGenerated").unwrap_or(&f.ty));
+                    let cursor_values = cursor_fields.iter().map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        if is_field_type(&f.ty, "AutoGenerated") {
+                            quote! { #field_name.0 }
+                        } else {
+                            quote! { #field_name }
+                        }
+                    });
+
+                    quote! {
+                        #[derive(Debug, Clone)]
+                        #vis struct #cursor_name {
+                            #( pub #cursor_names: #cursor_types ),*
+                        }
+
+                        impl ::leviosa::Keyset for #name {
+                            type Cursor = #cursor_name;
+                            const CURSOR_COLUMNS: &'static [&'static str] = &[#(#cursor_column_names),*];
+
+                            fn cursor(&self) -> Self::Cursor {
+                                #cursor_name {
+                                    #( #cursor_names: self.#cursor_values.clone() ),*
+                                }
+                            }
+
+                            fn bind_cursor(cursor: &Self::Cursor, values: &mut ::leviosa::BindValues) {
+                                #( values.push(cursor.#cursor_names.clone()); )*
+                            }
+                        }
+                    }
+                }
+            }
+            _ => quote! {},
+        }
+    } else {
+        quote! {}
+    };
+
+    // Related rows are fetched fresh on every call. `<relation>_cached` keeps the first result on
+    // the instance until `clear_<relation>_cache` is called. `has_<relation>` and
+    // `<relation>_count` ask the database without reading the rows.
+    let has_many_methods = has_many
+        .iter()
+        .zip(&cache_fields)
+        .map(|(relation, cache_field)| {
+            let related = &relation.ty;
+            let accessor = relation.accessor();
+            let cached_accessor = format_ident!("{}_cached", accessor);
+            let clear_cache = format_ident!("clear_{}_cache", accessor);
+            let has_related = format_ident!("has_{}", accessor);
+            let count_related = format_ident!("{}_count", accessor);
+            let foreign_key = quote_ident(&relation.foreign_key);
+            quote! {
+                #[must_use = "the result of the query should be handled"]
+                pub async fn #accessor<'a>(
+                    &self,
+                    executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                ) -> sqlx::Result<Vec<#related>> {
+                    let query = format!(
+                        "SELECT {} FROM {} WHERE {} = $1",
+                        <#related as ::leviosa::Table>::COLUMNS,
+                        <#related as ::leviosa::Table>::TABLE,
+                        #foreign_key
+                    );
+                    let mut connection = executor.acquire().await?;
+                    sqlx::query_as::<_, #related>(&query)
+                        .bind(&self.#primary_key_ident)
+                        .fetch_all(&mut *connection)
+                        .await
+                }
+
+                #[must_use = "the result of the query should be handled"]
+                pub async fn #cached_accessor<'a>(
+                    &self,
+                    executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                ) -> sqlx::Result<&[#related]> {
+                    if self.#cache_field.get().is_none() {
+                        let rows = self.#accessor(executor).await?;
+                        let _ = self.#cache_field.set(rows);
+                    }
+                    Ok(self.#cache_field.get().unwrap())
+                }
+
+                pub fn #clear_cache(&mut self) {
+                    self.#cache_field.take();
+                }
+
+                #[must_use = "the result of the query should be handled"]
+                pub async fn #has_related<'a>(
+                    &self,
+                    executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                ) -> sqlx::Result<bool> {
+                    let query = format!(
+                        "SELECT EXISTS(SELECT 1 FROM {} WHERE {} = $1)",
+                        <#related as ::leviosa::Table>::TABLE,
+                        #foreign_key
+                    );
+                    let mut connection = executor.acquire().await?;
+                    sqlx::query_scalar::<_, bool>(&query)
+                        .bind(&self.#primary_key_ident)
+                        .fetch_one(&mut *connection)
+                        .await
+                }
+
+                #[must_use = "the result of the query should be handled"]
+                pub async fn #count_related<'a>(
+                    &self,
+                    executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                ) -> sqlx::Result<i64> {
+                    let query = format!(
+                        "SELECT COUNT(*) FROM {} WHERE {} = $1",
+                        <#related as ::leviosa::Table>::TABLE,
+                        #foreign_key
+                    );
+                    let mut connection = executor.acquire().await?;
+                    sqlx::query_scalar::<_, i64>(&query)
+                        .bind(&self.#primary_key_ident)
+                        .fetch_one(&mut *connection)
+                        .await
+                }
+            }
+        });
+    let has_many_methods = quote! { #( #has_many_methods )* };
+
+    // Lets where_relation_count find a relation by its accessor name.
+    let relation_arms = has_many.iter().map(|relation| {
+        let related = &relation.ty;
+        let accessor = relation.accessor().to_string();
+        let correlation = format!(
+            "{} = {}.{}",
+            quote_ident(&relation.foreign_key),
+            table_name,
+            id_column
+        );
+        quote! {
+            #accessor => Some(format!(
+                "(SELECT COUNT(*) FROM {} WHERE {})",
+                <#related as ::leviosa::Table>::TABLE,
+                #correlation
+            )),
+        }
+    });
+    let has_many_impl = if has_many.is_empty() {
+        quote! {
+            impl ::leviosa::HasMany for #name {
+                fn relation_count_sql(_relation: &str) -> Option<String> {
+                    None
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl ::leviosa::HasMany for #name {
+                fn relation_count_sql(relation: &str) -> Option<String> {
+                    match relation {
+                        #( #relation_arms )*
+                        _ => None,
+                    }
+                }
+            }
+        }
+    };
+
+    // #[leviosa(timestamps)] structs keep an updated_at column, touch bumps it on its own.
+    let touch_method = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.timestamps => {
+                let updated_at = fields
+                    .named
+                    .iter()
+                    .find(|f| f.ident.as_ref().unwrap() == "updated_at");
+                let Some(updated_at) = updated_at else {
+                    return syn::Error::new_spanned(
+                        name,
+                        "leviosa: timestamps needs an `updated_at` field",
+                    )
+                    .to_compile_error()
+                    .into();
+                };
+                let updated_at_ty =
+                    extract_generic_type(&updated_at.ty, "AutoGenerated").unwrap_or(&updated_at.ty);
+                let updated_at_target = if is_field_type(&updated_at.ty, "AutoGenerated") {
+                    quote! { self.updated_at.0 }
+                } else {
+                    quote! { self.updated_at }
+                };
+                let touch_str = format!(
+                    "UPDATE {} SET {updated_at} = now() WHERE {} = $1 RETURNING {updated_at}",
+                    table_name,
+                    id_column,
+                    updated_at = quote_ident("updated_at")
+                );
+                quote! {
+                    // Sets updated_at to now() without changing anything else.
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn touch(&mut self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
+                        let updated_at = sqlx::query_scalar::<_, #updated_at_ty>(#touch_str)
+                            .bind(&self.#primary_key_ident)
+                            .fetch_optional(pool)
+                            .await?
+                            .ok_or(sqlx::Error::RowNotFound)?;
+                        #updated_at_target = updated_at;
+                        Ok(())
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    // created_before and friends need both columns, structs without created_at only get touch.
+    let timestamps_impl = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.timestamps => {
+                let has_created_at = fields
+                    .named
+                    .iter()
+                    .any(|f| f.ident.as_ref().unwrap() == "created_at");
+                let created_at = quote_ident("created_at");
+                let updated_at = quote_ident("updated_at");
+                if has_created_at {
+                    quote! {
+                        impl ::leviosa::Timestamps for #name {
+                            const CREATED_AT: &'static str = #created_at;
+                            const UPDATED_AT: &'static str = #updated_at;
+                        }
+                    }
+                } else {
+                    quote! {}
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    // #[group_key(author_id)] generates group_by_author, fetching the rows for many keys in one
+    // query so relations can be preloaded without a query per parent.
+    let group_keys = match group_keys(input) {
+        Ok(keys) => keys,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let mut group_by_methods = Vec::new();
+    if let Data::Struct(data) = &input.data {
+        if let Fields::Named(fields) = &data.fields {
+            for key in &group_keys {
+                let Some(field) = fields.named.iter().find(|f| f.ident.as_ref() == Some(key))
+                else {
+                    let message = format!("leviosa: group key `{}` is not a field", key);
+                    return syn::Error::new_spanned(key, message)
+                        .to_compile_error()
+                        .into();
+                };
+                let key_name = key.to_string();
+                let method_name = format_ident!(
+                    "group_by_{}",
+                    key_name.strip_suffix("_id").unwrap_or(&key_name)
+                );
+                // Rows with a NULL key belong to no group.
+                let (key_ty, key_value) = match extract_generic_type(&field.ty, "Option") {
+                    Some(inner) => (inner, quote! { row.#key.clone() }),
+                    None => (&field.ty, quote! { Some(row.#key.clone()) }),
+                };
+                let order_by = format!(" ORDER BY {}", id_column);
+                let query_str = format!(
+                    "SELECT {} FROM {} WHERE {} = ANY($1){}",
+                    select_list,
+                    table_name,
+                    quote_ident(&key_name),
+                    order_by
+                );
+                group_by_methods.push(quote! {
+                    // Keys without rows are left out of the map.
+                    #[must_use = "the result of the query should be handled"]
+                    pub async fn #method_name<'a>(
+                        executor: impl sqlx::Acquire<'a, Database = sqlx::Postgres>,
+                        keys: &[#key_ty],
+                    ) -> sqlx::Result<::std::collections::HashMap<#key_ty, Vec<Self>>>
+                    where
+                        for<'__leviosa> #key_ty: ::std::hash::Hash + Eq + Clone,
+                    {
+                        let mut connection = executor.acquire().await?;
+                        let rows = sqlx::query_as::<_, Self>(#query_str)
+                            .bind(keys)
+                            .fetch_all(&mut *connection)
+                            .await?;
+                        let mut groups = ::std::collections::HashMap::<#key_ty, Vec<Self>>::new();
+                        for row in rows {
+                            if let Some(key) = #key_value {
+                                groups.entry(key).or_default().push(row);
+                            }
+                        }
+                        Ok(groups)
+                    }
+                });
+            }
+        }
+    }
+    let group_by_methods = quote! { #( #group_by_methods )* };
+
+    // #[leviosa(checked)] also passes the fixed-shape statements through sqlx::query! so schema
+    // drift fails the build. The checks are never called, the methods keep binding at runtime so
+    // AutoGenerated, flag and skipped fields work as before.
+    let checked_queries = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if args.checked => {
+                // Checked at the top of standard_methods.
+                let primary_key = primary_key_field(fields).unwrap();
+                let id_value = if is_field_type(&primary_key.ty, "AutoGenerated") {
+                    quote! { entity.#primary_key_ident.0 }
+                } else {
+                    quote! { entity.#primary_key_ident }
+                };
+                let select_str = format!(
+                    "SELECT {} FROM {} WHERE {} = $1",
+                    select_list, table_name, id_column
+                );
+                let updates = fields
+                    .named
+                    .iter()
+                    .filter(|_| !args.view)
+                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated") && !is_skipped(f))
+                    .filter(|f| *f.ident.as_ref().unwrap() != primary_key_ident)
+                    .map(|f| {
+                        let field_name = f.ident.as_ref().unwrap();
+                        let update_str = format!(
+                            "UPDATE {} SET {} = $2{} WHERE {} = $1",
+                            table_name,
+                            quote_ident(&field_name.to_string()),
+                            placeholder_cast(f),
+                            id_column
+                        );
+                        // Flag columns store characters, only the statement is checked.
+                        let value = if column_options(f).bool_true.is_some() {
+                            quote! { entity.#field_name as _ }
+                        } else {
+                            quote! { entity.#field_name }
+                        };
+                        quote! {
+                            let _ = sqlx::query!(#update_str, #id_value, #value);
+                        }
+                    });
+                quote! {
+                    #[allow(dead_code)]
+                    #[doc(hidden)]
+                    fn __leviosa_checked_queries(entity: &Self) {
+                        let _ = sqlx::query!(#select_str, #id_value);
+                        #( #updates )*
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    // Deleting a row that is already gone is only an error with #[leviosa(strict_delete)].
+    let execute_delete = quote! {
+        sqlx::query(&query)
+            .bind(&self.#primary_key_ident)
+            .execute(pool)
+            .await?
+    };
+    let execute_delete = if args.strict_delete {
+        quote! {
+            if #execute_delete.rows_affected() == 0 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+    } else {
+        quote! {
+            #execute_delete;
+        }
+    };
+
+    let delete_method = quote! {
+        #[must_use = "the result of the query should be handled"]
+        pub async fn delete(&self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
+            let query = format!("DELETE FROM {} WHERE {} = $1", #table_name, #id_column);
+            #execute_delete
+            Ok(())
+        }
+    };
+
+    // Columns, keys and foreign keys only. Indexes, checks and defaults beyond the ones the
+    // database fills AutoGenerated fields with stay in the migrations.
+    let create_table_method = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                // Checked at the top of standard_methods.
+                let primary_key = primary_key_field(fields).unwrap();
+                let columns = fields.named.iter().filter(|f| !is_skipped(f)).map(|f| {
+                    column_definition(f, f.ident == primary_key.ident, args.quote_identifiers)
+                });
+                quote! {
+                    pub fn create_table_sql() -> String {
+                        let columns = [#(#columns),*];
+                        format!("CREATE TABLE {} ({})", #table_name, columns.join(", "))
+                    }
+                }
+            }
+            _ => quote! {},
+        },
+        _ => quote! {},
+    };
+
+    let try_from_columns = match &input.data {
+        Data::Struct(data) => data
+            .fields
+            .iter()
+            .map(|field| try_from_column(name, field))
+            .collect(),
+        _ => quote! {},
+    };
+
+    // #[leviosa(view)] structs are read-only, nothing that writes to the table is generated.
+    let (write_impls, write_methods) = if args.view {
+        (quote! {}, quote! {})
+    } else {
+        (
+            quote! {
+                #insert_impl
+                #repository_impl
+                #delete_all_query_builder
+                #update_query_builder
+            },
+            quote! {
+                #update_method
+                #update_rows_method
+                #change_tracking_methods
+                #changeset_method
+                #touch_method
+                #delete_method
+                #delete_all_method
+                #create_method
+                #create_table_method
+            },
+        )
+    };
+
+    let mut output = strip_leviosa_attributes(input);
+    // The caches aren't part of the row, nor of what serde reads or writes.
+    let serde_skip = if derives(input, "Serialize") || derives(input, "Deserialize") {
+        quote! { #[serde(skip)] }
+    } else {
+        quote! {}
+    };
+    if let Data::Struct(data) = &mut output.data {
+        if let Fields::Named(fields) = &mut data.fields {
+            for (relation, cache_field) in has_many.iter().zip(&cache_fields) {
+                let related = &relation.ty;
+                let field = syn::Field::parse_named.parse2(quote! {
+                    #[sqlx(skip)]
+                    #serde_skip
+                    #cache_field: ::std::sync::OnceLock<Vec<#related>>
+                });
+                fields.named.push(field.unwrap());
+            }
+        }
+    }
+
+    let standard = quote! {
+        #output
+
+        #table_impl
+        #has_many_impl
+        #keyset_impl
+        #timestamps_impl
+        #try_from_columns
+        #filter_impl
+        #changeset_struct
+        #find_all_query_builder
+        #write_impls
+
+        impl #name {
+            #methods
+            #get_by_ids_method
+            #find_all_method
+            #has_many_methods
+            #group_by_methods
+            #write_methods
+            #constructor
+            #same_as_method
+            #to_row_json_method
+            #checked_queries
+        }
+    };
+
+    TokenStream::from(standard)
+}
@@ -0,0 +1,765 @@
+use inflector::Inflector;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DeriveInput, Field, Fields, FieldsNamed, GenericArgument, Ident, Lit, Meta,
+    MetaNameValue, NestedMeta, Path, PathArguments, Type, TypePath,
+};
+
+pub fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            path.segments.last().map_or(false, |segment| {
+                segment.ident == "String" && segment.arguments.is_empty()
+            })
+        }
+        _ => false,
+    }
+}
+
+// `Vec<u8>` (BYTEA) fields are passed to create and setters as byte slices, so large blobs can
+// be written from borrowed data.
+pub fn is_bytes(ty: &Type) -> bool {
+    match extract_generic_type(ty, "Vec") {
+        Some(Type::Path(TypePath { path, .. })) => path.is_ident("u8"),
+        _ => false,
+    }
+}
+
+pub fn is_optional_bytes(ty: &Type) -> bool {
+    extract_generic_type(ty, "Option").map_or(false, is_bytes)
+}
+
+// `serde_json::Value` fields, stored as JSONB unless the column is `#[column(type = "json")]`.
+pub fn is_json_value(ty: &Type) -> bool {
+    let ty = extract_generic_type(ty, "Option").unwrap_or(ty);
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => {
+            path.segments.last().map_or(false, |segment| {
+                segment.ident == "Value" && segment.arguments.is_empty()
+            })
+        }
+        _ => false,
+    }
+}
+
+pub fn is_field_type(ty: &Type, field_type: &str) -> bool {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if path.segments.len() == 1 {
+            let segment = &path.segments[0];
+            // Adjust the condition to match the name of your AutoGenerated struct
+            if segment.ident == field_type {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    return args.args.len() == 1
+                        && matches!(args.args.first(), Some(GenericArgument::Type(_)));
+                }
+            }
+        }
+    }
+    false
+}
+
+pub fn extract_relation_generic_type<'a>(ty: &'a Type) -> Option<&'a Type> {
+    extract_generic_type(ty, "Relation")
+}
+
+pub fn extract_generic_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if path.segments.len() == 1 {
+            let segment = &path.segments[0];
+            // Check if the segment identifier is the wrapper, e.g. "Relation"
+            if segment.ident == wrapper {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    // Ensure there's exactly one generic argument and it's a Type
+                    if args.args.len() == 1 {
+                        if let Some(GenericArgument::Type(ty)) = args.args.first() {
+                            // Successfully found the generic type `T` in `Wrapper<T>`
+                            return Some(ty);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // Return None if the type is not `Wrapper<T>` or on failure to extract `T`
+    None
+}
+
+pub fn type_to_string_identifier(ty: &Type) -> Option<String> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        // Assuming a simple path with no generic parameters for the identifier.
+        let segments = &path.segments;
+        if segments.len() == 1 {
+            let segment = &segments[0];
+            return Some(segment.ident.to_string());
+        }
+    }
+    None
+}
+
+pub fn has_attribute(field: &Field, name: &str) -> bool {
+    field.attrs.iter().any(|attr| attr.path.is_ident(name))
+}
+
+// Whether the struct derives the trait, matched on the last path segment so `serde::Serialize`
+// counts as `Serialize`.
+pub fn derives(input: &DeriveInput, name: &str) -> bool {
+    input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.iter().any(|nested| {
+                matches!(nested, NestedMeta::Meta(Meta::Path(path))
+                    if path.segments.last().map_or(false, |segment| segment.ident == name))
+            }),
+            _ => false,
+        })
+}
+
+// Fields marked #[skip] live only on the rust side and are never read or written as columns.
+pub fn is_skipped(field: &Field) -> bool {
+    has_attribute(field, "skip")
+}
+
+// #[primary_key(generate = "uuid_v4")]: create generates the key in rust and inserts it with the row.
+pub fn generates_uuid_v4(field: &Field) -> bool {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("primary_key"))
+        .any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.iter().any(|nested| {
+                matches!(nested, NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                    path,
+                    lit: Lit::Str(value),
+                    ..
+                })) if path.is_ident("generate") && value.value() == "uuid_v4")
+            }),
+            _ => false,
+        })
+}
+
+// Rewrites leviosa field attributes into something the rest of the derives understand,
+// as attribute macros must not leave unknown attributes behind.
+pub fn strip_leviosa_attributes(input: &DeriveInput) -> DeriveInput {
+    let mut output = input.clone();
+    output
+        .attrs
+        .retain(|attr| !attr.path.is_ident("has_many") && !attr.path.is_ident("group_key"));
+    if let Data::Struct(data) = &mut output.data {
+        if let Fields::Named(fields) = &mut data.fields {
+            for field in fields.named.iter_mut() {
+                let skipped = is_skipped(field);
+                let options = column_options(field);
+                let oid = options.sql_type.as_deref() == Some("oid");
+                field.attrs.retain(|attr| {
+                    !attr.path.is_ident("skip")
+                        && !attr.path.is_ident("column")
+                        && !attr.path.is_ident("primary_key")
+                        && !attr.path.is_ident("unique")
+                        && !attr.path.is_ident("indexed")
+                });
+                if skipped {
+                    field.attrs.push(parse_quote!(#[sqlx(skip)]));
+                }
+                // Read as int8 by select_expression, u32 converts from i64.
+                if oid {
+                    field.attrs.push(parse_quote!(#[sqlx(try_from = "i64")]));
+                }
+                // Decrypted as it is read, see leviosa::Encrypted.
+                if let Some(cipher) = &options.encrypt_with {
+                    let try_from = format!("::leviosa::Encrypted<{}>", cipher);
+                    field
+                        .attrs
+                        .push(parse_quote!(#[sqlx(try_from = #try_from)]));
+                }
+                // Checked against the enum as it is read, see try_from_column.
+                if options.try_from.is_some() {
+                    let wrapper = try_from_column_name(&input.ident, field).to_string();
+                    field.attrs.push(parse_quote!(#[sqlx(try_from = #wrapper)]));
+                }
+                // Read through the interner, see leviosa::Interned.
+                if options.intern {
+                    field
+                        .attrs
+                        .push(parse_quote!(#[sqlx(try_from = "::leviosa::Interned")]));
+                }
+            }
+        }
+    }
+    output
+}
+
+// The field marked #[primary_key], otherwise the one named id.
+pub fn primary_key_field(fields: &FieldsNamed) -> Option<&Field> {
+    fields
+        .named
+        .iter()
+        .find(|f| has_attribute(f, "primary_key"))
+        .or_else(|| {
+            fields
+                .named
+                .iter()
+                .find(|f| f.ident.as_ref().map_or(false, |ident| ident == "id"))
+        })
+}
+
+// The type the database hands back for the primary key, AutoGenerated<T> yields T.
+pub fn primary_key_type(field: &Field) -> &Type {
+    extract_generic_type(&field.ty, "AutoGenerated").unwrap_or(&field.ty)
+}
+
+// A relation declared on the struct, e.g. #[has_many(Post, foreign_key = "user_id")].
+pub struct HasMany {
+    pub ty: Path,
+    pub foreign_key: String,
+}
+
+impl HasMany {
+    // The accessor is named after the related type, Post gives posts.
+    pub fn accessor(&self) -> Ident {
+        let ty = &self.ty.segments.last().unwrap().ident;
+        format_ident!("{}", ty.to_string().to_snake_case().to_plural())
+    }
+}
+
+pub fn has_many_relations(input: &DeriveInput) -> syn::Result<Vec<HasMany>> {
+    let mut relations = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("has_many"))
+    {
+        let mut ty = None;
+        let mut foreign_key = format!("{}_id", input.ident.to_string().to_snake_case());
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if ty.is_none() => ty = Some(path),
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(value),
+                        ..
+                    })) if path.is_ident("foreign_key") => foreign_key = value.value(),
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "leviosa: expected #[has_many(Type, foreign_key = \"...\")]",
+                        ))
+                    }
+                }
+            }
+        }
+        match ty {
+            Some(ty) => relations.push(HasMany { ty, foreign_key }),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "leviosa: has_many needs the related type",
+                ))
+            }
+        }
+    }
+    Ok(relations)
+}
+
+// Columns rows can be fetched grouped by, e.g. #[group_key(author_id)].
+pub fn group_keys(input: &DeriveInput) -> syn::Result<Vec<Ident>> {
+    let mut keys = Vec::new();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("group_key"))
+    {
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::Path(path)) if path.get_ident().is_some() => {
+                        keys.push(path.get_ident().unwrap().clone())
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "leviosa: expected #[group_key(field)]",
+                        ))
+                    }
+                }
+            }
+        }
+    }
+    Ok(keys)
+}
+
+// Options given through #[column(...)] on a field.
+#[derive(Debug, Default)]
+pub struct ColumnOptions {
+    pub sql_type: Option<String>,
+    pub bool_true: Option<String>,
+    pub bool_false: Option<String>,
+    pub db_generated: bool,
+    pub read_as: Option<String>,
+    pub intern: bool,
+    pub encrypt_with: Option<String>,
+    pub try_from: Option<String>,
+    pub on_delete: Option<String>,
+}
+
+pub fn column_options(field: &Field) -> ColumnOptions {
+    let mut options = ColumnOptions::default();
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("column"))
+    {
+        if let Ok(Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                        path,
+                        lit: Lit::Str(value),
+                        ..
+                    })) => {
+                        if path.is_ident("type") {
+                            options.sql_type = Some(value.value());
+                        } else if path.is_ident("bool_true") {
+                            options.bool_true = Some(value.value());
+                        } else if path.is_ident("bool_false") {
+                            options.bool_false = Some(value.value());
+                        } else if path.is_ident("as") {
+                            options.read_as = Some(value.value());
+                        } else if path.is_ident("encrypt_with") {
+                            options.encrypt_with = Some(value.value());
+                        } else if path.is_ident("try_from") {
+                            options.try_from = Some(value.value());
+                        } else if path.is_ident("on_delete") {
+                            options.on_delete = Some(value.value());
+                        }
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("db_generated") => {
+                        options.db_generated = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("intern") => {
+                        options.intern = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    options
+}
+
+// Columns create sends. AutoGenerated and #[column(db_generated)] values, e.g. set by a trigger,
+// come from the database and are only read back.
+pub fn is_inserted(field: &Field) -> bool {
+    !is_field_type(&field.ty, "AutoGenerated")
+        && !is_skipped(field)
+        && !column_options(field).db_generated
+}
+
+// NaiveDateTime maps to TIMESTAMP and DateTime<Tz> to TIMESTAMPTZ. Casting a bound value to the
+// other type converts it through the session time zone and shifts the stored time.
+pub fn timestamp_cast_error(field: &Field) -> Option<syn::Error> {
+    let sql_type = column_options(field).sql_type?.to_lowercase();
+    let mut ty = &field.ty;
+    while let Some(inner) =
+        extract_generic_type(ty, "Option").or_else(|| extract_generic_type(ty, "AutoGenerated"))
+    {
+        ty = inner;
+    }
+    let Type::Path(TypePath { path, .. }) = ty else {
+        return None;
+    };
+    let type_name = path.segments.last()?.ident.to_string();
+    let message = match (type_name.as_str(), sql_type.as_str()) {
+        ("NaiveDateTime", "timestamptz" | "timestamp with time zone") => {
+            "leviosa: NaiveDateTime maps to TIMESTAMP, use DateTime<Utc> for a TIMESTAMPTZ column"
+        }
+        ("DateTime", "timestamp" | "timestamp without time zone") => {
+            "leviosa: DateTime maps to TIMESTAMPTZ, use NaiveDateTime for a TIMESTAMP column"
+        }
+        _ => return None,
+    };
+    Some(syn::Error::new_spanned(&field.ty, message))
+}
+
+// #[column(type = "numeric", as = "f64")] reads the column converted to the field's type, losing
+// whatever precision the float can't hold.
+fn read_cast(read_as: &str) -> Option<&'static str> {
+    match read_as {
+        "f64" => Some("float8"),
+        "f32" => Some("float4"),
+        _ => None,
+    }
+}
+
+pub fn read_as_error(field: &Field) -> Option<syn::Error> {
+    let read_as = column_options(field).read_as?;
+    match read_cast(&read_as) {
+        Some(_) => None,
+        None => Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(as = ...)] takes \"f64\" or \"f32\"",
+        )),
+    }
+}
+
+pub fn intern_error(field: &Field) -> Option<syn::Error> {
+    if column_options(field).intern && !is_field_type(&field.ty, "Arc") {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(intern)] needs an `Arc<str>` field",
+        ));
+    }
+    None
+}
+
+// AutoGenerated fields are filled in by the database and never inserted, a key generated in rust
+// has to be.
+pub fn generate_error(field: &Field) -> Option<syn::Error> {
+    if generates_uuid_v4(field) && is_field_type(&field.ty, "AutoGenerated") {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[primary_key(generate = \"uuid_v4\")] needs a `Uuid` field, not `AutoGenerated`",
+        ));
+    }
+    None
+}
+
+// The related type of a `Relation<T>` or `Option<Relation<T>>` field.
+pub fn relation_type(ty: &Type) -> Option<&Type> {
+    extract_relation_generic_type(extract_generic_type(ty, "Option").unwrap_or(ty))
+}
+
+const ON_DELETE_ACTIONS: [&str; 3] = ["cascade", "set null", "restrict"];
+
+// SET NULL writes NULL into the referencing column, which only an Option field can read back.
+pub fn on_delete_error(field: &Field) -> Option<syn::Error> {
+    let on_delete = column_options(field).on_delete?;
+    let message = if !ON_DELETE_ACTIONS.contains(&on_delete.as_str()) {
+        "leviosa: #[column(on_delete = ...)] takes \"cascade\", \"set null\" or \"restrict\""
+    } else if relation_type(&field.ty).is_none() {
+        "leviosa: #[column(on_delete = ...)] needs a `Relation<T>` field"
+    } else if on_delete == "set null" && !is_field_type(&field.ty, "Option") {
+        "leviosa: #[column(on_delete = \"set null\")] needs an `Option<Relation<T>>` field"
+    } else {
+        return None;
+    };
+    Some(syn::Error::new_spanned(&field.ty, message))
+}
+
+// The column as create_table_sql declares it. The type is the name sqlx gives the field's type,
+// unless the field is converted on its way in and out of the database.
+pub fn column_definition(field: &Field, primary_key: bool, quoted: bool) -> TokenStream {
+    let options = column_options(field);
+    let column_name = quote_identifier(&field.ident.as_ref().unwrap().to_string(), quoted);
+    let optional = is_field_type(&field.ty, "Option");
+    let ty = extract_generic_type(&field.ty, "Option").unwrap_or(&field.ty);
+    let auto_generated = extract_generic_type(ty, "AutoGenerated");
+    let ty = auto_generated.unwrap_or(ty);
+    let type_name = match ty {
+        Type::Path(TypePath { path, .. }) => path
+            .segments
+            .last()
+            .map_or(String::new(), |segment| segment.ident.to_string()),
+        _ => String::new(),
+    };
+    let serial = match type_name.as_str() {
+        "i16" => Some("SMALLSERIAL"),
+        "i32" => Some("SERIAL"),
+        "i64" => Some("BIGSERIAL"),
+        _ => None,
+    };
+
+    let sql_type = if let Some(sql_type) = options.sql_type {
+        quote! { #sql_type }
+    } else if let (Some(serial), Some(_)) = (serial, auto_generated) {
+        quote! { #serial }
+    } else if options.encrypt_with.is_some() {
+        quote! { "BYTEA" }
+    } else if options.intern || is_bool_flag(field) {
+        quote! { "TEXT" }
+    } else if let Some(try_from) = options.try_from {
+        let int = format_ident!("{}", try_from);
+        quote! { sqlx::TypeInfo::name(&<#int as sqlx::Type<sqlx::Postgres>>::type_info()) }
+    } else if has_sqlx_flag(field, "json") {
+        quote! { "JSONB" }
+    } else if let Some(decoded) = sqlx_try_from(field) {
+        quote! { sqlx::TypeInfo::name(&<#decoded as sqlx::Type<sqlx::Postgres>>::type_info()) }
+    } else {
+        quote! { sqlx::TypeInfo::name(&<#ty as sqlx::Type<sqlx::Postgres>>::type_info()) }
+    };
+
+    let mut constraints = String::new();
+    if primary_key {
+        constraints.push_str(" PRIMARY KEY");
+    } else if !optional {
+        constraints.push_str(" NOT NULL");
+    }
+    if has_attribute(field, "unique") {
+        constraints.push_str(" UNIQUE");
+    }
+    if auto_generated.is_some() {
+        match type_name.as_str() {
+            "Uuid" => constraints.push_str(" DEFAULT gen_random_uuid()"),
+            "DateTime" | "NaiveDateTime" => constraints.push_str(" DEFAULT now()"),
+            _ => {}
+        }
+    }
+
+    // Relations point at the id of the related table, as load_<field> reads it.
+    let references = match relation_type(&field.ty) {
+        Some(related) => {
+            let on_delete = options
+                .on_delete
+                .map(|action| format!(" ON DELETE {}", action.to_uppercase()))
+                .unwrap_or_default();
+            quote! {
+                format!(
+                    " REFERENCES {} (id){}",
+                    <#related as ::leviosa::Table>::TABLE,
+                    #on_delete
+                )
+            }
+        }
+        None => quote! { String::new() },
+    };
+
+    quote! {
+        format!("{} {}{}{}", #column_name, #sql_type, #constraints, #references)
+    }
+}
+
+// Fields the user decodes through #[sqlx(json)] or #[sqlx(try_from = "...")] have no sqlx type
+// of their own, the column holds the JSON or the type converted from.
+fn sqlx_options(field: &Field) -> Vec<NestedMeta> {
+    field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("sqlx"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested.into_iter()),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+fn has_sqlx_flag(field: &Field, flag: &str) -> bool {
+    sqlx_options(field)
+        .iter()
+        .any(|nested| matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident(flag)))
+}
+
+fn sqlx_try_from(field: &Field) -> Option<Type> {
+    sqlx_options(field)
+        .into_iter()
+        .find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path,
+                lit: Lit::Str(value),
+                ..
+            })) if path.is_ident("try_from") => value.parse().ok(),
+            _ => None,
+        })
+}
+
+const TRY_FROM_TYPES: [&str; 3] = ["i16", "i32", "i64"];
+
+pub fn try_from_error(field: &Field) -> Option<syn::Error> {
+    let try_from = column_options(field).try_from?;
+    if !TRY_FROM_TYPES.contains(&try_from.as_str()) {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(try_from = ...)] takes \"i16\", \"i32\" or \"i64\"",
+        ));
+    }
+    if is_field_type(&field.ty, "Option") {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(try_from = ...)] needs a field that isn't an `Option`",
+        ));
+    }
+    None
+}
+
+// The hidden type a #[column(try_from = "...")] field is decoded through, see try_from_column.
+pub fn try_from_column_name(name: &Ident, field: &Field) -> Ident {
+    let field_name = field.ident.as_ref().unwrap().to_string();
+    format_ident!("__Leviosa{}{}", name, field_name.to_pascal_case())
+}
+
+// Decodes the integer and converts it with the field type's TryFrom, so a value without a
+// variant fails the read with leviosa::InvalidEnum naming the column.
+pub fn try_from_column(name: &Ident, field: &Field) -> TokenStream {
+    let Some(try_from) = column_options(field).try_from else {
+        return quote! {};
+    };
+    let int = format_ident!("{}", try_from);
+    let ty = &field.ty;
+    let column_name = field.ident.as_ref().unwrap().to_string();
+    let wrapper = try_from_column_name(name, field);
+    quote! {
+        #[doc(hidden)]
+        struct #wrapper(#ty);
+
+        impl sqlx::Type<sqlx::Postgres> for #wrapper {
+            fn type_info() -> sqlx::postgres::PgTypeInfo {
+                <#int as sqlx::Type<sqlx::Postgres>>::type_info()
+            }
+
+            fn compatible(ty: &sqlx::postgres::PgTypeInfo) -> bool {
+                <#int as sqlx::Type<sqlx::Postgres>>::compatible(ty)
+            }
+        }
+
+        impl<'r> sqlx::Decode<'r, sqlx::Postgres> for #wrapper {
+            fn decode(
+                value: sqlx::postgres::PgValueRef<'r>,
+            ) -> Result<Self, sqlx::error::BoxDynError> {
+                let value = <#int as sqlx::Decode<sqlx::Postgres>>::decode(value)?;
+                match <#ty as ::std::convert::TryFrom<#int>>::try_from(value) {
+                    Ok(value) => Ok(#wrapper(value)),
+                    Err(_) => Err(Box::new(::leviosa::InvalidEnum {
+                        column: #column_name,
+                        value: i64::from(value),
+                    })),
+                }
+            }
+        }
+
+        impl ::std::convert::From<#wrapper> for #ty {
+            fn from(value: #wrapper) -> Self {
+                value.0
+            }
+        }
+    }
+}
+
+pub fn encrypt_error(field: &Field) -> Option<syn::Error> {
+    let cipher = column_options(field).encrypt_with?;
+    if syn::parse_str::<Path>(&cipher).is_err() {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(encrypt_with = ...)] takes the path of a `leviosa::Cipher` type",
+        ));
+    }
+    if !is_string(&field.ty) && !is_bytes(&field.ty) {
+        return Some(syn::Error::new_spanned(
+            &field.ty,
+            "leviosa: #[column(encrypt_with = ...)] needs a `String` or `Vec<u8>` field",
+        ));
+    }
+    None
+}
+
+// Cast appended to a placeholder, e.g. $1::json for #[column(type = "json")].
+pub fn placeholder_cast(field: &Field) -> String {
+    match column_options(field).sql_type {
+        // Casting to char(n) would cut longer values, text is padded or rejected on write.
+        Some(sql_type) if is_char_type(&sql_type) => String::new(),
+        Some(sql_type) => format!("::{}", sql_type),
+        None => String::new(),
+    }
+}
+
+// char(n) and character(n), which Postgres pads with spaces up to n.
+fn is_char_type(sql_type: &str) -> bool {
+    let sql_type = sql_type.to_lowercase();
+    let name = sql_type.split('(').next().unwrap_or_default().trim();
+    matches!(name, "char" | "character" | "bpchar")
+}
+
+// A bool stored as a pair of flag values, e.g. 'Y'/'N' in a char(1) column.
+fn bool_flags(field: &Field) -> Option<(String, String)> {
+    let options = column_options(field);
+    match (options.bool_true, options.bool_false) {
+        (Some(bool_true), Some(bool_false)) => Some((bool_true, bool_false)),
+        _ => None,
+    }
+}
+
+pub fn is_bool_flag(field: &Field) -> bool {
+    bool_flags(field).is_some()
+}
+
+// Wraps a table or column name in double quotes when quoting is on, so reserved words work.
+pub fn quote_identifier(identifier: &str, quoted: bool) -> String {
+    if quoted {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    } else {
+        identifier.to_string()
+    }
+}
+
+// How a column appears in the select list, flag columns are compared back into a bool and
+// #[column(as = ...)], oid and char(n) columns are cast.
+pub fn select_expression(field: &Field, quoted: bool) -> String {
+    let column = quote_identifier(&field.ident.as_ref().unwrap().to_string(), quoted);
+    let options = column_options(field);
+    if let Some(cast) = options.read_as.as_deref().and_then(read_cast) {
+        return format!("{}::{} AS {}", column, cast, column);
+    }
+    if options.sql_type.as_deref() == Some("oid") {
+        return format!("{}::int8 AS {}", column, column);
+    }
+    match bool_flags(field) {
+        Some((bool_true, _)) => format!(
+            "({} = '{}') AS {}",
+            column,
+            bool_true.replace('\'', "''"),
+            column
+        ),
+        // Converting to text drops the padding.
+        None if options.sql_type.as_deref().map_or(false, is_char_type) => {
+            format!("{}::text AS {}", column, column)
+        }
+        None => column,
+    }
+}
+
+// The expression bound for a column value. `value` may be the value itself or a reference to it.
+pub fn bind_value(field: &Field, value: TokenStream) -> TokenStream {
+    let options = column_options(field);
+    if let Some((bool_true, bool_false)) = bool_flags(field) {
+        return if is_field_type(&field.ty, "Option") {
+            quote! {
+                ::std::borrow::Borrow::<Option<bool>>::borrow(&(#value))
+                    .map(|value| if value { #bool_true } else { #bool_false })
+            }
+        } else {
+            quote! {
+                if *::std::borrow::Borrow::<bool>::borrow(&(#value)) { #bool_true } else { #bool_false }
+            }
+        };
+    }
+    // Fieldless enums convert to their discriminant with `as`.
+    if let Some(try_from) = options.try_from {
+        let int = format_ident!("{}", try_from);
+        let ty = &field.ty;
+        return quote! { (*::std::borrow::Borrow::<#ty>::borrow(&(#value))) as #int };
+    }
+    // The ciphertext is what gets stored, and compared against.
+    if let Some(cipher) = options.encrypt_with {
+        let cipher: Path = syn::parse_str(&cipher).unwrap();
+        return quote! {
+            <#cipher as ::leviosa::Cipher>::encrypt(::std::convert::AsRef::<[u8]>::as_ref(&(#value)))
+        };
+    }
+    // sqlx 0.7 can't encode Arc<str>, the text is bound as a String.
+    if options.intern {
+        return quote! { ::std::convert::AsRef::<str>::as_ref(&(#value)).to_owned() };
+    }
+    match options.sql_type.as_deref() {
+        // serde_json::Value binds as JSONB, which would normalize the document.
+        // Binding the text and casting keeps it exactly as serialized.
+        Some("json") if is_field_type(&field.ty, "Option") => {
+            quote! { (#value).as_ref().map(|value| value.to_string()) }
+        }
+        Some("json") => quote! { (#value).to_string() },
+        // sqlx has no u32 mapping, the value travels as int8 and is cast to oid.
+        Some("oid") => quote! { i64::from(*::std::borrow::Borrow::<u32>::borrow(&(#value))) },
+        _ => value,
+    }
+}
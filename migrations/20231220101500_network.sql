@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE network_struct (
+    id SERIAL PRIMARY KEY,
+    inet_field INET,
+    cidr_field CIDR
+);
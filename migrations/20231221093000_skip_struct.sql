@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE skip_struct (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
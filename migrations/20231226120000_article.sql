@@ -0,0 +1,16 @@
+-- Add migration script here
+
+-- search_vector is maintained by the trigger below and is not part of the rust struct.
+CREATE TABLE article (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    search_vector TSVECTOR
+);
+
+CREATE INDEX article_search_vector_idx ON article USING GIN (search_vector);
+
+CREATE TRIGGER article_search_vector_update
+    BEFORE INSERT OR UPDATE ON article
+    FOR EACH ROW EXECUTE FUNCTION
+    tsvector_update_trigger(search_vector, 'pg_catalog.english', title, body);
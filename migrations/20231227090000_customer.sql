@@ -0,0 +1,12 @@
+-- Add migration script here
+
+CREATE TYPE address AS (
+    street TEXT,
+    city TEXT
+);
+
+CREATE TABLE customer (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    address address
+);
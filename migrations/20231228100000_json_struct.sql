@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE json_struct (
+    id SERIAL PRIMARY KEY,
+    json_field JSON,
+    jsonb_field JSONB
+);
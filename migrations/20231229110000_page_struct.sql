@@ -0,0 +1,8 @@
+-- Add migration script here
+
+CREATE TABLE page_struct (
+    id SERIAL PRIMARY KEY,
+    created TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX page_struct_created_id_idx ON page_struct (created, id);
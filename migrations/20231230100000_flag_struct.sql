@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE flag_struct (
+    id SERIAL PRIMARY KEY,
+    active CHAR(1) NOT NULL,
+    archived CHAR(1)
+);
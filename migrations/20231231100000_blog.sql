@@ -0,0 +1,12 @@
+-- Add migration script here
+
+CREATE TABLE blog (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL
+);
+
+CREATE TABLE blog_post (
+    id SERIAL PRIMARY KEY,
+    blog_id INT NOT NULL REFERENCES blog (id) ON DELETE CASCADE,
+    body TEXT NOT NULL
+);
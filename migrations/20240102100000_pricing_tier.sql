@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE pricing_tier (
+    id SERIAL PRIMARY KEY,
+    prices NUMERIC[]
+);
@@ -0,0 +1,10 @@
+-- Add migration script here
+
+CREATE EXTENSION IF NOT EXISTS ltree;
+
+CREATE TABLE category (
+    id SERIAL PRIMARY KEY,
+    path LTREE NOT NULL
+);
+
+CREATE INDEX category_path_idx ON category USING GIST (path);
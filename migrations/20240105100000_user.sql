@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE "user" (
+    id SERIAL PRIMARY KEY,
+    "select" VARCHAR(255) NOT NULL,
+    "order" INT NOT NULL
+);
@@ -0,0 +1,11 @@
+-- Add migration script here
+
+CREATE TABLE truncate_parent (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
+
+CREATE TABLE truncate_child (
+    id SERIAL PRIMARY KEY,
+    truncate_parent_id INT NOT NULL REFERENCES truncate_parent (id)
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE float_struct (
+    id SERIAL PRIMARY KEY,
+    real_field REAL,
+    double_field DOUBLE PRECISION
+);
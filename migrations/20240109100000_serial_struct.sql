@@ -0,0 +1,11 @@
+-- Add migration script here
+
+CREATE TABLE big_serial_struct (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
+
+CREATE TABLE small_serial_struct (
+    id SMALLSERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE schedule (
+    id SERIAL PRIMARY KEY,
+    dates DATE[],
+    reminders TIMESTAMP WITH TIME ZONE[]
+);
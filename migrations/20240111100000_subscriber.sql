@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE subscriber (
+    id SERIAL PRIMARY KEY,
+    email VARCHAR(255) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL
+);
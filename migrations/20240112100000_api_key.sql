@@ -0,0 +1,7 @@
+-- Add migration script here
+
+-- No DEFAULT on the key, the id is generated by the client.
+CREATE TABLE api_key (
+    id UUID PRIMARY KEY,
+    label VARCHAR(255) NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE checked_struct (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    score INTEGER
+);
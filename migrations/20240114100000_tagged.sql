@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE tagged (
+    id SERIAL PRIMARY KEY,
+    tag_ids INT[] NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE counted (
+    id SERIAL PRIMARY KEY,
+    label VARCHAR(255) NOT NULL
+);
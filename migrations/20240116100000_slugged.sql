@@ -0,0 +1,17 @@
+-- Add migration script here
+
+CREATE TABLE slugged (
+    id SERIAL PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    slug VARCHAR(255) NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION slugged_set_slug() RETURNS trigger AS $$
+BEGIN
+    NEW.slug := lower(replace(NEW.title, ' ', '-'));
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER slugged_set_slug BEFORE INSERT ON slugged
+    FOR EACH ROW EXECUTE FUNCTION slugged_set_slug();
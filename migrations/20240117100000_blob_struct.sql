@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE blob_struct (
+    id SERIAL PRIMARY KEY,
+    data BYTEA NOT NULL,
+    thumbnail BYTEA
+);
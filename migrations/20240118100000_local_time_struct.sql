@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE local_time_struct (
+    id SERIAL PRIMARY KEY,
+    starts_at TIMESTAMP NOT NULL,
+    logged_at TIMESTAMPTZ NOT NULL
+);
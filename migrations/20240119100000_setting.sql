@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE setting (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE survey_answer (
+    id SERIAL PRIMARY KEY,
+    survey TEXT NOT NULL,
+    answer BOOLEAN
+);
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE member (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL
+);
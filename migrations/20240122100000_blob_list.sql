@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE blob_list (
+    id SERIAL PRIMARY KEY,
+    chunks BYTEA[] NOT NULL,
+    seen_at TIMESTAMP[]
+);
@@ -0,0 +1,8 @@
+-- Add migration script here
+
+CREATE TABLE profile (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    bio TEXT
+);
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE measurement (
+    id SERIAL PRIMARY KEY,
+    reading NUMERIC(10, 2) NOT NULL
+);
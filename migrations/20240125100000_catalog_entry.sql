@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE catalog_entry (
+    id SERIAL PRIMARY KEY,
+    type_name TEXT NOT NULL,
+    type_oid OID NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE status_event (
+    id SERIAL PRIMARY KEY,
+    status TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE patient (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    ssn BYTEA NOT NULL
+);
@@ -0,0 +1,4 @@
+-- Add migration script here
+
+CREATE VIEW profile_summary AS
+    SELECT id, email, name FROM profile;
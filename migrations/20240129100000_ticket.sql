@@ -0,0 +1,7 @@
+-- Add migration script here
+
+CREATE TABLE ticket (
+    id SERIAL PRIMARY KEY,
+    title TEXT NOT NULL,
+    priority INT NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Add migration script here
+
+CREATE TABLE soft_deleted_subscriber (
+    id SERIAL PRIMARY KEY,
+    email TEXT NOT NULL,
+    name TEXT NOT NULL,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX soft_deleted_subscriber_active_email ON soft_deleted_subscriber (email) WHERE deleted_at IS NULL;
@@ -0,0 +1,3 @@
+-- Add migration script here
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
@@ -0,0 +1,6 @@
+-- Add migration script here
+
+CREATE TABLE fixed_code (
+    id SERIAL PRIMARY KEY,
+    code CHAR(10) NOT NULL
+);
@@ -0,0 +1,17 @@
+-- Add migration script here
+
+-- No primary key, the partition key is nullable and a key on a partitioned table has to
+-- include it. Rows without a date go to the default partition.
+CREATE TABLE partitioned_reading (
+    id SERIAL,
+    logged_on DATE,
+    reading INT NOT NULL
+) PARTITION BY RANGE (logged_on);
+
+CREATE TABLE partitioned_reading_2024 PARTITION OF partitioned_reading
+    FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');
+
+CREATE TABLE partitioned_reading_2025 PARTITION OF partitioned_reading
+    FOR VALUES FROM ('2025-01-01') TO ('2026-01-01');
+
+CREATE TABLE partitioned_reading_undated PARTITION OF partitioned_reading DEFAULT;
@@ -0,0 +1,56 @@
+use std::marker::PhantomData;
+
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Postgres, Type};
+
+/// Encrypts `#[column(encrypt_with = "...")]` columns, named by the type implementing it. The
+/// column is a `BYTEA` holding the ciphertext, the field holds the plaintext.
+pub trait Cipher {
+    /// Called on the field's bytes for every value written, including values compared in
+    /// `get_by_<field>`.
+    fn encrypt(plaintext: &[u8]) -> Vec<u8>;
+
+    /// Called on the stored bytes for every value read, an error fails the read.
+    fn decrypt(ciphertext: &[u8]) -> Result<Vec<u8>, BoxDynError>;
+}
+
+/// A column decrypted with `C` as it is read, converted into the field's `String` or `Vec<u8>`.
+pub struct Encrypted<C> {
+    plaintext: Vec<u8>,
+    cipher: PhantomData<C>,
+}
+
+impl<C> Type<Postgres> for Encrypted<C> {
+    fn type_info() -> PgTypeInfo {
+        <&[u8] as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&[u8] as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r, C: Cipher> Decode<'r, Postgres> for Encrypted<C> {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let ciphertext = <&[u8] as Decode<Postgres>>::decode(value)?;
+        Ok(Encrypted {
+            plaintext: C::decrypt(ciphertext)?,
+            cipher: PhantomData,
+        })
+    }
+}
+
+impl<C> From<Encrypted<C>> for Vec<u8> {
+    fn from(value: Encrypted<C>) -> Self {
+        value.plaintext
+    }
+}
+
+impl<C> TryFrom<Encrypted<C>> for String {
+    type Error = std::string::FromUtf8Error;
+
+    fn try_from(value: Encrypted<C>) -> Result<Self, Self::Error> {
+        String::from_utf8(value.plaintext)
+    }
+}
@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// The source of the `sqlx::Error::ColumnDecode` returned when a `#[column(try_from = "...")]`
+/// column holds a value its enum has no variant for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEnum {
+    pub column: &'static str,
+    pub value: i64,
+}
+
+impl fmt::Display for InvalidEnum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid enum value {} in column `{}`",
+            self.value, self.column
+        )
+    }
+}
+
+impl std::error::Error for InvalidEnum {}
+
+/// The source of the `sqlx::Error::Configuration` returned when a `distinct_on` query is executed
+/// without an ordering that starts with the `DISTINCT ON` columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDistinctOn {
+    pub columns: Vec<String>,
+    pub order_by: Option<String>,
+}
+
+impl fmt::Display for InvalidDistinctOn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "distinct_on({}) needs an ordering starting with those columns, got {}",
+            self.columns.join(", "),
+            self.order_by.as_deref().unwrap_or("none")
+        )
+    }
+}
+
+impl std::error::Error for InvalidDistinctOn {}
+
+/// The source of the `sqlx::Error::Configuration` returned when a query filtered with
+/// `where_relation_count` is executed and the struct has no `#[has_many]` relation by that name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownRelation {
+    pub table: &'static str,
+    pub relation: String,
+}
+
+impl fmt::Display for UnknownRelation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` has no has_many relation `{}`",
+            self.table, self.relation
+        )
+    }
+}
+
+impl std::error::Error for UnknownRelation {}
+
+/// The source of the `sqlx::Error::Configuration` returned when an upsert is executed without
+/// `on_conflict`, Postgres can't run `DO UPDATE` without knowing which conflict it is for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingConflictTarget {
+    pub update_columns: Vec<String>,
+}
+
+impl fmt::Display for MissingConflictTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "upsert updating {} needs on_conflict",
+            self.update_columns.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for MissingConflictTarget {}
+
+/// Maps the `sqlx::Result` leviosa methods return into an application error type at the call
+/// site, any `E` implementing `From<sqlx::Error>`.
+pub trait IntoAppError<T> {
+    /// The error converted with `E::from`.
+    fn app_err<E: From<sqlx::Error>>(self) -> Result<T, E>;
+
+    /// Like `app_err`, but `sqlx::Error::RowNotFound`, returned by setters and `update()` when
+    /// the row is gone, becomes `missing()`.
+    fn or_missing<E: From<sqlx::Error>>(self, missing: impl FnOnce() -> E) -> Result<T, E>;
+}
+
+impl<T> IntoAppError<T> for sqlx::Result<T> {
+    fn app_err<E: From<sqlx::Error>>(self) -> Result<T, E> {
+        self.map_err(E::from)
+    }
+
+    fn or_missing<E: From<sqlx::Error>>(self, missing: impl FnOnce() -> E) -> Result<T, E> {
+        match self {
+            Err(sqlx::Error::RowNotFound) => Err(missing()),
+            result => result.map_err(E::from),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::future::Future;
+
+use sqlx::PgPool;
+
+/// Lifecycle callbacks the inserts of `#[leviosa(hooks)]` structs run, `create`, `create_many`
+/// and the methods built on them. Both do nothing unless overridden.
+pub trait Hooks {
+    /// Runs before the row is inserted, changes made to `self` are stored.
+    fn before_create(&mut self) {}
+
+    /// Runs once the row is inserted, with the row as the database returned it. An error is
+    /// returned from the insert, the row stays inserted.
+    fn after_create(&self, _pool: &PgPool) -> impl Future<Output = sqlx::Result<()>> + Send {
+        async { Ok(()) }
+    }
+}
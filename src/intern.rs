@@ -0,0 +1,63 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Postgres, Type};
+
+/// Text read through the process wide interner, so equal values share one allocation.
+/// `#[column(intern)]` fields are read as this and converted into their `Arc<str>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Interned(Arc<str>);
+
+impl Type<Postgres> for Interned {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Interned {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        <&str as Decode<Postgres>>::decode(value).map(|value| Interned(intern(value)))
+    }
+}
+
+impl From<Interned> for Arc<str> {
+    fn from(value: Interned) -> Self {
+        value.0
+    }
+}
+
+struct Interner {
+    values: HashSet<Arc<str>>,
+    // Values nothing else holds anymore are dropped once the set grows past this.
+    prune_at: usize,
+}
+
+/// Returns the shared `Arc<str>` for `value`, allocating it the first time it is seen.
+pub fn intern(value: &str) -> Arc<str> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    let interner = INTERNER.get_or_init(|| {
+        Mutex::new(Interner {
+            values: HashSet::new(),
+            prune_at: 1024,
+        })
+    });
+    let mut interner = interner
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(existing) = interner.values.get(value) {
+        return existing.clone();
+    }
+    if interner.values.len() >= interner.prune_at {
+        interner.values.retain(|value| Arc::strong_count(value) > 1);
+        interner.prune_at = (interner.values.len() * 2).max(1024);
+    }
+    let interned: Arc<str> = Arc::from(value);
+    interner.values.insert(interned.clone());
+    interned
+}
@@ -1,44 +1,41 @@
-use inflector::Inflector;
-use proc_macro::TokenStream;
-use quote::format_ident;
-use quote::quote;
-use syn::parse::{Parse, ParseStream};
-use syn::punctuated::Punctuated;
-use syn::Ident;
-use syn::{
-    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, ItemStruct, PathArguments,
-    Token, Type, TypePath,
+pub use leviosa_macros::leviosa;
+
+mod cipher;
+mod error;
+mod hooks;
+mod intern;
+mod pool;
+#[cfg(feature = "postgis")]
+pub mod postgis;
+mod query;
+mod repository;
+mod retry;
+mod stream;
+mod transaction;
+
+pub use cipher::{Cipher, Encrypted};
+pub use error::{
+    IntoAppError, InvalidDistinctOn, InvalidEnum, MissingConflictTarget, UnknownRelation,
 };
-
-mod many_to_many;
-mod standard;
-mod utils;
-
-#[derive(Debug)]
-struct LeviosaArgs {
-    many_to_many: bool,
-}
-
-impl Parse for LeviosaArgs {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let args = Punctuated::<Ident, Token![,]>::parse_terminated(input)?;
-
-        let many_to_many = args.iter().any(|ident| ident == "many_to_many");
-
-        Ok(LeviosaArgs { many_to_many })
-    }
-}
-
-#[proc_macro_attribute]
-pub fn leviosa(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as DeriveInput);
-
-    let args = parse_macro_input!(_attr as LeviosaArgs);
-    let name = &input.ident;
-
-    if args.many_to_many {
-        many_to_many::many_to_many_methods(name, &input)
-    } else {
-        standard::standard_methods(name, &input)
-    }
+pub use futures::Stream;
+pub use hooks::Hooks;
+pub use intern::{intern, Interned};
+pub use pool::{connect, connect_with, pool_options};
+pub use query::{
+    BindValues, ConditionGroup, DateTrunc, DeleteAllQueryBuilder, Filter, FindAllQueryBuilder,
+    HasMany, Insert, InsertQueryBuilder, IntoBindValues, Keyset, LockMode, Op, Order, Page,
+    PreparedInsert, Projection, QueryBuilderState, SqlEnum, Table, Timestamps,
+};
+pub use repository::Repository;
+pub use retry::{with_acquire_retry, AcquireRetry};
+#[doc(hidden)]
+pub use serde;
+#[doc(hidden)]
+pub use serde_json;
+pub use stream::{insert_batches, DEFAULT_BATCH_SIZE};
+pub use transaction::with_transaction;
+
+/// Generates keys for `#[primary_key(generate = "uuid_v4")]`.
+pub fn uuid_v4() -> uuid::Uuid {
+    uuid::Uuid::new_v4()
 }
@@ -0,0 +1,146 @@
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueFormat, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+
+const WKB_POINT: u32 = 1;
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+
+/// WGS 84, longitude and latitude in degrees.
+pub const SRID_WGS84: u32 = 4326;
+
+/// A PostGIS `geometry(Point)` column. Points are written as EWKB with SRID 4326, so `x` is the
+/// longitude and `y` the latitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point(pub geo_types::Point<f64>);
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self(geo_types::Point::new(x, y))
+    }
+
+    pub fn x(&self) -> f64 {
+        self.0.x()
+    }
+
+    pub fn y(&self) -> f64 {
+        self.0.y()
+    }
+}
+
+impl From<geo_types::Point<f64>> for Point {
+    fn from(point: geo_types::Point<f64>) -> Self {
+        Self(point)
+    }
+}
+
+impl From<Point> for geo_types::Point<f64> {
+    fn from(point: Point) -> Self {
+        point.0
+    }
+}
+
+impl Type<Postgres> for Point {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("geometry")
+    }
+}
+
+impl Encode<'_, Postgres> for Point {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        buf.push(1); // little endian
+        buf.extend_from_slice(&(WKB_POINT | EWKB_SRID_FLAG).to_le_bytes());
+        buf.extend_from_slice(&SRID_WGS84.to_le_bytes());
+        buf.extend_from_slice(&self.x().to_le_bytes());
+        buf.extend_from_slice(&self.y().to_le_bytes());
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Point {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        // The text format is the same EWKB, hex encoded.
+        let bytes = match value.format() {
+            PgValueFormat::Binary => value.as_bytes()?.to_vec(),
+            PgValueFormat::Text => decode_hex(value.as_str()?)?,
+        };
+        decode_ewkb_point(&bytes)
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, BoxDynError> {
+    if hex.len() % 2 != 0 {
+        return Err("invalid hex encoded geometry".into());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(Into::into))
+        .collect()
+}
+
+fn decode_ewkb_point(bytes: &[u8]) -> Result<Point, BoxDynError> {
+    let mut reader = WkbReader::new(bytes)?;
+    let geometry_type = reader.read_u32()?;
+    if geometry_type & (EWKB_Z_FLAG | EWKB_M_FLAG) != 0 {
+        return Err("only 2D points are supported".into());
+    }
+    if geometry_type & 0xff != WKB_POINT {
+        return Err(format!(
+            "expected a point geometry, got type {}",
+            geometry_type & 0xff
+        )
+        .into());
+    }
+    if geometry_type & EWKB_SRID_FLAG != 0 {
+        reader.read_u32()?;
+    }
+    let x = reader.read_f64()?;
+    let y = reader.read_f64()?;
+    Ok(Point::new(x, y))
+}
+
+struct WkbReader<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> WkbReader<'a> {
+    fn new(bytes: &'a [u8]) -> Result<Self, BoxDynError> {
+        match bytes.split_first() {
+            Some((&order, rest)) => Ok(Self {
+                bytes: rest,
+                little_endian: order == 1,
+            }),
+            None => Err("empty geometry".into()),
+        }
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], BoxDynError> {
+        if self.bytes.len() < N {
+            return Err("truncated geometry".into());
+        }
+        let (head, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    fn read_u32(&mut self) -> Result<u32, BoxDynError> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self) -> Result<f64, BoxDynError> {
+        let bytes = self.take::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+}
@@ -0,0 +1,1653 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::postgres::types::PgInterval;
+use sqlx::postgres::PgRow;
+use sqlx::{
+    Acquire, Encode, FromRow, PgConnection, PgPool, Postgres, QueryBuilder, Type, TypeInfo,
+};
+
+use crate::error::{InvalidDistinctOn, MissingConflictTarget, UnknownRelation};
+
+/// Table metadata, implemented for every `#[leviosa]` struct.
+pub trait Table {
+    const TABLE: &'static str;
+    /// The select list, used in place of `*` so columns can be converted on read.
+    const COLUMNS: &'static str;
+}
+
+/// Keyset pagination, implemented for every `#[leviosa]` struct.
+/// The cursor columns default to the primary key, see `#[leviosa(cursor = "created, id")]`.
+pub trait Keyset: Table {
+    type Cursor: Clone + Debug + Send + Sync;
+    const CURSOR_COLUMNS: &'static [&'static str];
+
+    fn cursor(&self) -> Self::Cursor;
+
+    fn bind_cursor(cursor: &Self::Cursor, values: &mut BindValues);
+}
+
+/// Single row inserts, implemented for every `#[leviosa]` struct.
+pub trait Insert: Table {
+    /// `INSERT INTO table (columns) `, AutoGenerated and skipped fields are left to the database.
+    const INSERT: &'static str;
+    /// The columns `upsert` overwrites by default, the inserted ones except the primary key.
+    const UPSERT_COLUMNS: &'static [&'static str];
+
+    /// Pushes the `VALUES (...)` clause binding the entity's fields.
+    fn push_values<'q>(&'q self, query_builder: &mut QueryBuilder<'q, Postgres>);
+}
+
+/// The managed timestamp columns of `#[leviosa(timestamps)]` structs that also have a
+/// `created_at` field, filtered on by `created_before` and friends.
+pub trait Timestamps: Table {
+    const CREATED_AT: &'static str;
+    const UPDATED_AT: &'static str;
+}
+
+/// The `#[has_many]` relations of a struct, implemented for every `#[leviosa]` struct.
+pub trait HasMany: Table {
+    /// `(SELECT COUNT(*) FROM related WHERE ...)` correlated with the outer row, for the
+    /// relation whose accessor is `relation`, e.g. `posts`.
+    fn relation_count_sql(relation: &str) -> Option<String>;
+}
+
+/// Conditions applied to a `find()` query in one go, implemented by the `<Struct>Filter`
+/// generated for `#[leviosa(filter)]` structs.
+pub trait Filter<T> {
+    fn apply(&self, query: &mut FindAllQueryBuilder<T>);
+}
+
+/// Postgres enum types, implemented for every `#[leviosa]` enum.
+pub trait SqlEnum {
+    /// The type name, `#[sqlx(type_name = "...")]` or the enum's name.
+    const TYPE_NAME: &'static str;
+
+    /// The label stored for the variant, following the sqlx renames.
+    fn as_sql_str(&self) -> &'static str;
+}
+
+/// What a `find()` builder holds, read through `state()` so logging and metrics don't have
+/// to parse the SQL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryBuilderState<'a> {
+    pub table: &'static str,
+    /// The WHERE conditions in order, with the `$n` placeholders the query binds them to.
+    pub conditions: Vec<String>,
+    pub order_by: Option<&'a str>,
+    pub limit: Option<usize>,
+    /// The number of bound values, including those of unions.
+    pub binds: usize,
+}
+
+/// A page of rows, `next` is the cursor to continue from when the page was full.
+#[derive(Debug, Clone)]
+pub struct Page<T: Keyset> {
+    pub rows: Vec<T>,
+    pub next: Option<T::Cursor>,
+}
+
+/// Comparison operators for `filter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl Op {
+    fn as_sql(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "<>",
+            Op::Lt => "<",
+            Op::Lte => "<=",
+            Op::Gt => ">",
+            Op::Gte => ">=",
+        }
+    }
+}
+
+/// Sort direction for `order_by_field`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+/// Precision for `where_date_trunc_eq`, the `date_trunc` field names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateTrunc {
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl DateTrunc {
+    fn as_sql(self) -> &'static str {
+        match self {
+            DateTrunc::Day => "day",
+            DateTrunc::Week => "week",
+            DateTrunc::Month => "month",
+            DateTrunc::Year => "year",
+        }
+    }
+}
+
+/// Row lock taken by `lock`, from the strongest to the weakest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockMode {
+    /// `FOR UPDATE`, for rows about to be deleted or have their key changed.
+    Update,
+    /// `FOR NO KEY UPDATE`, for rows about to be updated without touching the key. Doesn't
+    /// block inserts referencing the rows.
+    NoKeyUpdate,
+    /// `FOR SHARE`, keeps the rows from changing until the transaction ends.
+    Share,
+    /// `FOR KEY SHARE`, only keeps the rows from being deleted or having their key changed.
+    KeyShare,
+}
+
+impl LockMode {
+    fn as_sql(self) -> &'static str {
+        match self {
+            LockMode::Update => "FOR UPDATE",
+            LockMode::NoKeyUpdate => "FOR NO KEY UPDATE",
+            LockMode::Share => "FOR SHARE",
+            LockMode::KeyShare => "FOR KEY SHARE",
+        }
+    }
+}
+
+/// Values bound to a query in order.
+#[derive(Clone, Debug, Default)]
+pub struct BindValues {
+    values: Vec<Arc<dyn Bindable>>,
+}
+
+impl BindValues {
+    pub fn push<V>(&mut self, value: V)
+    where
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.values.push(Arc::new(value));
+    }
+}
+
+/// Values for the `$n` placeholders of `where_raw`, a tuple such as `(18,)` or `(min, max)`.
+pub trait IntoBindValues {
+    fn into_bind_values(self) -> BindValues;
+}
+
+impl IntoBindValues for BindValues {
+    fn into_bind_values(self) -> BindValues {
+        self
+    }
+}
+
+macro_rules! tuple_bind_values {
+    ($($name:ident),+) => {
+        impl<$($name),+> IntoBindValues for ($($name,)+)
+        where
+            $($name: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,)+
+        {
+            #[allow(non_snake_case)]
+            fn into_bind_values(self) -> BindValues {
+                let ($($name,)+) = self;
+                let mut values = BindValues::default();
+                $(values.push($name);)+
+                values
+            }
+        }
+    };
+}
+
+tuple_bind_values!(A);
+tuple_bind_values!(A, B);
+tuple_bind_values!(A, B, C);
+tuple_bind_values!(A, B, C, D);
+tuple_bind_values!(A, B, C, D, E);
+tuple_bind_values!(A, B, C, D, E, F);
+
+// A value bound to a query, type erased so conditions over different types can live together.
+trait Bindable: Debug + Send + Sync {
+    fn push_bind(&self, query_builder: &mut QueryBuilder<'_, Postgres>);
+
+    fn sql_literal(&self) -> String;
+}
+
+impl<V> Bindable for V
+where
+    V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+{
+    fn push_bind(&self, query_builder: &mut QueryBuilder<'_, Postgres>) {
+        query_builder.push_bind(self.clone());
+    }
+
+    fn sql_literal(&self) -> String {
+        sql_literal(
+            <V as Type<Postgres>>::type_info().name(),
+            &format!("{:?}", self),
+        )
+    }
+}
+
+// The value as a SQL literal, read back from its Debug output. Only for showing a query, arrays
+// and composite values come out as rust prints them.
+fn sql_literal(type_name: &str, debug: &str) -> String {
+    let value = match debug
+        .strip_prefix("Some(")
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        Some(value) => value,
+        None if debug == "None" => return String::from("NULL"),
+        None => debug,
+    };
+    match type_name {
+        "BOOL" | "INT2" | "INT4" | "INT8" | "OID" | "NUMERIC" => return String::from(value),
+        "FLOAT4" | "FLOAT8" if value.parse::<f64>().map_or(false, f64::is_finite) => {
+            return String::from(value);
+        }
+        _ => {}
+    }
+    // Text prints as a quoted rust string with its escapes.
+    let text = match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(escaped) => unescape(escaped),
+        None => String::from(value),
+    };
+    let literal = format!("'{}'", text.replace('\'', "''"));
+    match type_name {
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" => literal,
+        _ => format!("{}::{}", literal, type_name),
+    }
+}
+
+fn unescape(escaped: &str) -> String {
+    let mut text = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('0') => text.push('\0'),
+            // \u{1f989}
+            Some('u') => {
+                let code: String = chars.by_ref().skip(1).take_while(|c| *c != '}').collect();
+                if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    text.push(c);
+                }
+            }
+            Some(c) => text.push(c),
+            None => {}
+        }
+    }
+    text
+}
+
+// Intervals have microsecond precision, anything finer is dropped and anything past i64
+// microseconds is clamped.
+fn interval(duration: Duration) -> PgInterval {
+    PgInterval {
+        months: 0,
+        days: 0,
+        microseconds: i64::try_from(duration.as_micros()).unwrap_or(i64::MAX),
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Fragment {
+    Sql(String),
+    Bind(Arc<dyn Bindable>),
+}
+
+// A single condition, placeholders are numbered when the query is built.
+#[derive(Clone, Debug, Default)]
+struct Condition {
+    fragments: Vec<Fragment>,
+}
+
+impl Condition {
+    fn sql(mut self, sql: &str) -> Self {
+        self.fragments.push(Fragment::Sql(String::from(sql)));
+        self
+    }
+
+    fn bind<V>(mut self, value: V) -> Self
+    where
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.fragments.push(Fragment::Bind(Arc::new(value)));
+        self
+    }
+
+    fn bind_list(mut self, values: BindValues) -> Self {
+        for (i, value) in values.values.into_iter().enumerate() {
+            if i > 0 {
+                self.fragments.push(Fragment::Sql(String::from(", ")));
+            }
+            self.fragments.push(Fragment::Bind(value));
+        }
+        self
+    }
+
+    // Splits `sql` on its `$n` placeholders, binding the n-th value in each one's place.
+    fn raw(sql: &str, values: BindValues) -> Self {
+        let mut condition = Condition::default();
+        let mut rest = sql;
+        while let Some(start) = rest.find('$') {
+            let digits = rest[start + 1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - start - 1);
+            if digits == 0 {
+                condition = condition.sql(&rest[..=start]);
+                rest = &rest[start + 1..];
+                continue;
+            }
+            let index: usize = rest[start + 1..start + 1 + digits].parse().unwrap_or(0);
+            let Some(value) = index.checked_sub(1).and_then(|i| values.values.get(i)) else {
+                panic!("where_raw: ${} has no value bound to it", index);
+            };
+            condition = condition.sql(&rest[..start]);
+            condition.fragments.push(Fragment::Bind(Arc::clone(value)));
+            rest = &rest[start + 1 + digits..];
+        }
+        condition.sql(rest)
+    }
+
+    // The condition's SQL, numbering its placeholders from `placeholder`.
+    fn to_sql(&self, placeholder: &mut usize) -> String {
+        let mut sql = String::new();
+        for fragment in &self.fragments {
+            match fragment {
+                Fragment::Sql(fragment) => sql.push_str(fragment),
+                Fragment::Bind(_) => {
+                    sql.push_str(&format!("${}", placeholder));
+                    *placeholder += 1;
+                }
+            }
+        }
+        sql
+    }
+
+    fn bind_count(&self) -> usize {
+        self.fragments
+            .iter()
+            .filter(|fragment| matches!(fragment, Fragment::Bind(_)))
+            .count()
+    }
+
+    fn bind_literals(&self) -> impl Iterator<Item = String> + '_ {
+        self.fragments.iter().filter_map(|fragment| match fragment {
+            Fragment::Bind(value) => Some(value.sql_literal()),
+            Fragment::Sql(_) => None,
+        })
+    }
+
+    fn render(&self, query_builder: &mut QueryBuilder<'_, Postgres>) {
+        for fragment in &self.fragments {
+            match fragment {
+                Fragment::Sql(sql) => {
+                    query_builder.push(sql);
+                }
+                Fragment::Bind(value) => value.push_bind(query_builder),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct Conditions {
+    conditions: Vec<Condition>,
+}
+
+impl Conditions {
+    fn push(&mut self, condition: Condition) {
+        self.conditions.push(condition);
+    }
+
+    // The conditions as a single parenthesized condition, e.g. (a OR b).
+    fn group(self, separator: &str) -> Option<Condition> {
+        if self.conditions.is_empty() {
+            return None;
+        }
+        let mut group = Condition::default().sql("(");
+        for (i, condition) in self.conditions.into_iter().enumerate() {
+            if i > 0 {
+                group = group.sql(separator);
+            }
+            group.fragments.extend(condition.fragments);
+        }
+        Some(group.sql(")"))
+    }
+
+    fn render_where(&self, query_builder: &mut QueryBuilder<'_, Postgres>) {
+        for (i, condition) in self.conditions.iter().enumerate() {
+            query_builder.push(if i == 0 { " WHERE " } else { " AND " });
+            condition.render(query_builder);
+        }
+    }
+}
+
+// Condition methods shared by every builder with a WHERE clause.
+macro_rules! condition_methods {
+    () => {
+        /// Raw WHERE condition, NOT sanitized.
+        pub fn r#where(&mut self, condition: &str) -> &mut Self {
+            self.conditions.push(Condition::default().sql(condition));
+            self
+        }
+
+        /// Raw WHERE condition with bound values, NOT sanitized. `$1`, `$2`... refer to the
+        /// values in order and are renumbered in the built query, e.g.
+        /// `where_raw("age BETWEEN $1 AND $2", (18, 65))`. Panics when a placeholder has no
+        /// value.
+        pub fn where_raw(&mut self, condition: &str, values: impl IntoBindValues) -> &mut Self {
+            self.conditions
+                .push(Condition::raw(condition, values.into_bind_values()));
+            self
+        }
+
+        /// Raw conditions joined with AND in parentheses, NOT sanitized.
+        /// An empty list adds nothing.
+        pub fn where_all<S: AsRef<str>>(
+            &mut self,
+            conditions: impl IntoIterator<Item = S>,
+        ) -> &mut Self {
+            let mut group = Conditions::default();
+            for condition in conditions {
+                group.push(Condition::default().sql(condition.as_ref()));
+            }
+            if let Some(condition) = group.group(" AND ") {
+                self.conditions.push(condition);
+            }
+            self
+        }
+
+        /// Same as `r#where`.
+        pub fn select(&mut self, _where: &str) -> &mut Self {
+            self.r#where(_where)
+        }
+
+        /// Conditions added in `group` joined with AND, in parentheses.
+        pub fn and(
+            &mut self,
+            group: impl FnOnce(&mut ConditionGroup) -> &mut ConditionGroup,
+        ) -> &mut Self {
+            let mut builder = ConditionGroup::default();
+            group(&mut builder);
+            if let Some(condition) = builder.conditions.group(" AND ") {
+                self.conditions.push(condition);
+            }
+            self
+        }
+
+        /// Conditions added in `group` joined with OR, in parentheses.
+        pub fn or(
+            &mut self,
+            group: impl FnOnce(&mut ConditionGroup) -> &mut ConditionGroup,
+        ) -> &mut Self {
+            let mut builder = ConditionGroup::default();
+            group(&mut builder);
+            if let Some(condition) = builder.conditions.group(" OR ") {
+                self.conditions.push(condition);
+            }
+            self
+        }
+
+        /// `column <op> $1` with the value bound, the column is NOT sanitized.
+        pub fn filter<V>(&mut self, column: &str, op: Op, value: V) -> &mut Self
+        where
+            V: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} {} ", column, op.as_sql()))
+                    .bind(value),
+            );
+            self
+        }
+
+        /// `left <op> right` comparing two columns, e.g. one of an outer query in a
+        /// `where_exists_related` subquery. NOT sanitized.
+        pub fn where_columns(&mut self, left: &str, op: Op, right: &str) -> &mut Self {
+            self.r#where(&format!("{} {} {}", left, op.as_sql(), right))
+        }
+
+        /// `EXISTS (SELECT 1 FROM related WHERE ...)` with the conditions of `subquery`, which
+        /// can refer to the outer table by name through `where_columns`. Its alias is kept, its
+        /// ordering, limit and select list are not used.
+        pub fn where_exists_related<U: Table>(
+            &mut self,
+            subquery: &FindAllQueryBuilder<U>,
+        ) -> &mut Self {
+            let mut condition =
+                Condition::default().sql(&format!("EXISTS (SELECT 1 FROM {}", U::TABLE));
+            if let Some(alias) = &subquery.alias {
+                condition = condition.sql(&format!(" AS {}", alias));
+            }
+            for (i, related) in subquery.conditions.conditions.iter().enumerate() {
+                condition = condition.sql(if i == 0 { " WHERE " } else { " AND " });
+                condition
+                    .fragments
+                    .extend(related.fragments.iter().cloned());
+            }
+            self.conditions.push(condition.sql(")"));
+            self
+        }
+
+        /// `column = $1` with the value bound, the column is NOT sanitized.
+        /// `= NULL` matches no row, NULL is found with `where_null`.
+        pub fn where_eq<V>(&mut self, column: &str, value: V) -> &mut Self
+        where
+            V: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.filter(column, Op::Eq, value)
+        }
+
+        /// `column = $1::<type>` for a `#[leviosa]` enum, binding the variant's label cast to the
+        /// enum type. The column is NOT sanitized.
+        pub fn where_enum_eq<E: SqlEnum>(&mut self, column: &str, value: E) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} = ", column))
+                    .bind(String::from(value.as_sql_str()))
+                    .sql(&format!("::{}", E::TYPE_NAME)),
+            );
+            self
+        }
+
+        /// Rows whose timestamp falls on the calendar day `value`, `column::date = $1`. The
+        /// column is NOT sanitized. `timestamptz` columns are cut at midnight in the session
+        /// time zone.
+        pub fn where_date_eq<V>(&mut self, column: &str, value: V) -> &mut Self
+        where
+            V: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{}::date = ", column))
+                    .bind(value),
+            );
+            self
+        }
+
+        /// Rows whose timestamp is more than `age` in the past, `column < now() - $1::interval`
+        /// with `age` bound as an interval. The column is NOT sanitized. `now()` is the time the
+        /// transaction started.
+        pub fn where_older_than(&mut self, column: &str, age: Duration) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} < now() - ", column))
+                    .bind(interval(age))
+                    .sql("::interval"),
+            );
+            self
+        }
+
+        /// Rows whose timestamp is less than `age` in the past, `column > now() - $1::interval`.
+        /// The column is NOT sanitized.
+        pub fn where_newer_than(&mut self, column: &str, age: Duration) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} > now() - ", column))
+                    .bind(interval(age))
+                    .sql("::interval"),
+            );
+            self
+        }
+
+        /// Rows in the same day, week, month or year as `value`,
+        /// `date_trunc('month', column) = date_trunc('month', $1)`. The column is NOT sanitized.
+        pub fn where_date_trunc_eq<V>(
+            &mut self,
+            column: &str,
+            trunc: DateTrunc,
+            value: V,
+        ) -> &mut Self
+        where
+            V: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            let field = trunc.as_sql();
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!(
+                        "date_trunc('{}', {}) = date_trunc('{}', ",
+                        field, column, field
+                    ))
+                    .bind(value)
+                    .sql(")"),
+            );
+            self
+        }
+
+        /// Case-insensitive equality for text columns, `lower(column) = lower($1)` with the
+        /// value bound. The column is NOT sanitized. An index on `lower(column)` serves it.
+        pub fn where_ieq(&mut self, column: &str, value: &str) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("lower({}) = lower(", column))
+                    .bind(String::from(value))
+                    .sql(")"),
+            );
+            self
+        }
+
+        /// `column IS NULL`, the column is NOT sanitized.
+        pub fn where_null(&mut self, column: &str) -> &mut Self {
+            self.r#where(&format!("{} IS NULL", column))
+        }
+
+        /// `column IS NOT NULL`, the column is NOT sanitized.
+        pub fn where_not_null(&mut self, column: &str) -> &mut Self {
+            self.r#where(&format!("{} IS NOT NULL", column))
+        }
+
+        /// Conditions added in `group` joined with AND and negated, `NOT (...)`.
+        /// Rows where the group is NULL are not matched either way.
+        pub fn not(
+            &mut self,
+            group: impl FnOnce(&mut ConditionGroup) -> &mut ConditionGroup,
+        ) -> &mut Self {
+            let mut builder = ConditionGroup::default();
+            group(&mut builder);
+            if let Some(condition) = builder.conditions.group(" AND ") {
+                let mut negated = Condition::default().sql("NOT ");
+                negated.fragments.extend(condition.fragments);
+                self.conditions.push(negated);
+            }
+            self
+        }
+
+        /// `column @> $1`, rows whose array holds every one of `values`.
+        pub fn where_array_contains<V>(&mut self, column: &str, values: &[V]) -> &mut Self
+        where
+            V: Clone,
+            Vec<V>: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} @> ", column))
+                    .bind(values.to_vec()),
+            );
+            self
+        }
+
+        /// `column && $1`, rows whose array holds at least one of `values`.
+        pub fn where_array_overlaps<V>(&mut self, column: &str, values: &[V]) -> &mut Self
+        where
+            V: Clone,
+            Vec<V>: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} && ", column))
+                    .bind(values.to_vec()),
+            );
+            self
+        }
+
+        /// `column = ANY($1)`, rows whose column is one of `values`, bound as one array.
+        pub fn where_in<V>(&mut self, column: &str, values: &[V]) -> &mut Self
+        where
+            V: Clone,
+            Vec<V>: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} = ANY(", column))
+                    .bind(values.to_vec())
+                    .sql(")"),
+            );
+            self
+        }
+
+        /// Same rows as `where_in`, written as `column IN (SELECT unnest($1))`, which the planner
+        /// may plan differently for lists of many thousands. `benches/where_in.rs` times both.
+        pub fn where_in_unnest<V>(&mut self, column: &str, values: &[V]) -> &mut Self
+        where
+            V: Clone,
+            Vec<V>: for<'q> Encode<'q, Postgres>
+                + Type<Postgres>
+                + Clone
+                + Debug
+                + Send
+                + Sync
+                + 'static,
+        {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} IN (SELECT unnest(", column))
+                    .bind(values.to_vec())
+                    .sql("))"),
+            );
+            self
+        }
+
+        /// Full text search against a `tsvector` column: `column @@ to_tsquery($1)`.
+        pub fn where_text_search(&mut self, column: &str, query: &str) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} @@ to_tsquery(", column))
+                    .bind(String::from(query))
+                    .sql(")"),
+            );
+            self
+        }
+
+        /// Typo tolerant matching with the `pg_trgm` extension: `similarity(column, $1) > $2`,
+        /// similarity going from 0 for no shared trigram to 1 for the same text.
+        pub fn where_similar(&mut self, column: &str, value: &str, threshold: f32) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("similarity({}, ", column))
+                    .bind(String::from(value))
+                    .sql(") > ")
+                    .bind(threshold),
+            );
+            self
+        }
+
+        /// Rows within `meters` of `point`, measured on the spheroid:
+        /// `ST_DWithin(column::geography, $1::geography, $2)`.
+        #[cfg(feature = "postgis")]
+        pub fn where_dwithin(
+            &mut self,
+            column: &str,
+            point: crate::postgis::Point,
+            meters: f64,
+        ) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("ST_DWithin({}::geography, ", column))
+                    .bind(point)
+                    .sql("::geography, ")
+                    .bind(meters)
+                    .sql(")"),
+            );
+            self
+        }
+
+        /// `column @> $1::ltree`, rows whose path is an ancestor of `path` or `path` itself.
+        pub fn where_ltree_ancestor(&mut self, column: &str, path: &str) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} @> ", column))
+                    .bind(String::from(path))
+                    .sql("::ltree"),
+            );
+            self
+        }
+
+        /// `column <@ $1::ltree`, rows whose path is a descendant of `path` or `path` itself.
+        pub fn where_ltree_descendant(&mut self, column: &str, path: &str) -> &mut Self {
+            self.conditions.push(
+                Condition::default()
+                    .sql(&format!("{} <@ ", column))
+                    .bind(String::from(path))
+                    .sql("::ltree"),
+            );
+            self
+        }
+    };
+}
+
+/// A group of conditions passed to `and` and `or`, groups can be nested.
+#[derive(Clone, Debug, Default)]
+pub struct ConditionGroup {
+    conditions: Conditions,
+}
+
+impl ConditionGroup {
+    condition_methods!();
+}
+
+/// Returned by `find()`, conditions added to the builder are joined with AND.
+#[must_use = "builders do nothing unless executed"]
+pub struct FindAllQueryBuilder<T> {
+    select_list: Option<String>,
+    limit: Option<usize>,
+    conditions: Conditions,
+    order_by_clause: Option<String>,
+    statement_timeout: Option<Duration>,
+    unions: Vec<(&'static str, FindAllQueryBuilder<T>)>,
+    expressions: Vec<(String, String)>,
+    lock: Option<LockMode>,
+    skip_locked: bool,
+    distinct_on: Vec<String>,
+    alias: Option<String>,
+    unknown_relation: Option<String>,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for FindAllQueryBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            select_list: self.select_list.clone(),
+            limit: self.limit,
+            conditions: self.conditions.clone(),
+            order_by_clause: self.order_by_clause.clone(),
+            statement_timeout: self.statement_timeout,
+            unions: self.unions.clone(),
+            expressions: self.expressions.clone(),
+            lock: self.lock,
+            skip_locked: self.skip_locked,
+            distinct_on: self.distinct_on.clone(),
+            alias: self.alias.clone(),
+            unknown_relation: self.unknown_relation.clone(),
+            entity: PhantomData,
+        }
+    }
+}
+
+impl<T: Table> FindAllQueryBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            select_list: None,
+            limit: None,
+            conditions: Conditions::default(),
+            order_by_clause: None,
+            statement_timeout: None,
+            unions: Vec::new(),
+            expressions: Vec::new(),
+            lock: None,
+            skip_locked: false,
+            distinct_on: Vec::new(),
+            alias: None,
+            unknown_relation: None,
+            entity: PhantomData,
+        }
+    }
+
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    condition_methods!();
+
+    /// Locks the rows read until the transaction ends, run it on a transaction.
+    pub fn lock(&mut self, mode: LockMode) -> &mut Self {
+        self.lock = Some(mode);
+        self
+    }
+
+    /// Leaves out rows another transaction holds a lock on instead of waiting for them,
+    /// `SKIP LOCKED`. Only applies along with `lock`, e.g. to claim jobs from a queue table.
+    pub fn skip_locked(&mut self) -> &mut Self {
+        self.skip_locked = true;
+        self
+    }
+
+    /// Adds the conditions of `filter`, e.g. a `<Struct>Filter` read from query parameters.
+    pub fn apply(&mut self, filter: &impl Filter<T>) -> &mut Self {
+        filter.apply(self);
+        self
+    }
+
+    /// Names an expression so the select list, conditions and ordering can use `name` in its
+    /// place, NOT sanitized. The table is then read as
+    /// `(SELECT *, expression AS name FROM table) AS table`.
+    pub fn expr(&mut self, name: &str, expression: &str) -> &mut Self {
+        self.expressions
+            .push((String::from(name), String::from(expression)));
+        self
+    }
+
+    /// Reads the table as `table AS alias`, so raw conditions can tell its columns from those of
+    /// the same table joined in a subquery, e.g. `alias.parent_id`. NOT sanitized.
+    pub fn alias(&mut self, alias: &str) -> &mut Self {
+        self.alias = Some(String::from(alias));
+        self
+    }
+
+    /// The query reading each row as `P`, e.g. a tuple or a struct with a subset of the
+    /// columns. The select list stays the entity columns unless narrowed with `columns`.
+    pub fn into<P>(&self) -> Projection<T, P> {
+        Projection {
+            query: self.clone(),
+            row: PhantomData,
+        }
+    }
+
+    /// Raw select list replacing the entity columns, NOT sanitized. Read it with `execute_as`.
+    pub fn select_raw(&mut self, select_list: &str) -> &mut Self {
+        self.select_list = Some(String::from(select_list));
+        self
+    }
+
+    /// Keeps the first row of each group of rows with equal `columns`, `DISTINCT ON (columns)`.
+    /// The ordering picks which row is first and must start with the same columns, otherwise the
+    /// query fails with `InvalidDistinctOn` when it runs. NOT sanitized.
+    pub fn distinct_on(&mut self, columns: &[&str]) -> &mut Self {
+        self.distinct_on = columns.iter().map(|column| String::from(*column)).collect();
+        self
+    }
+
+    pub fn order_by(&mut self, order_by: &str) -> &mut Self {
+        self.order_by_clause = Some(String::from(order_by));
+        self
+    }
+
+    /// `n` random rows, `ORDER BY random() LIMIT n`. Replaces any ordering and limit, every
+    /// matching row is read to pick them so keep it to filtered or modest tables.
+    pub fn sample(&mut self, n: usize) -> &mut Self {
+        self.order_by_clause = Some(String::from("random()"));
+        self.limit = Some(n);
+        self
+    }
+
+    /// Raw ORDER BY clause replacing any ordering so far, NOT sanitized.
+    pub fn order_by_raw(&mut self, order_by: &str) -> &mut Self {
+        self.order_by(order_by)
+    }
+
+    /// Orders by `column` after any ordering so far, the column is NOT sanitized.
+    pub fn order_by_field(&mut self, column: &str, order: Order) -> &mut Self {
+        let direction = match order {
+            Order::Asc => "ASC",
+            Order::Desc => "DESC",
+        };
+        let clause = format!("{} {}", column, direction);
+        self.order_by_clause = Some(match self.order_by_clause.take() {
+            Some(order_by) => format!("{}, {}", order_by, clause),
+            None => clause,
+        });
+        self
+    }
+
+    /// Puts NULLs first for the last ordering column. Postgres puts them last
+    /// ascending and first descending otherwise.
+    pub fn nulls_first(&mut self) -> &mut Self {
+        self.nulls("NULLS FIRST")
+    }
+
+    /// Puts NULLs last for the last ordering column.
+    pub fn nulls_last(&mut self) -> &mut Self {
+        self.nulls("NULLS LAST")
+    }
+
+    fn nulls(&mut self, placement: &str) -> &mut Self {
+        if let Some(order_by) = &mut self.order_by_clause {
+            order_by.push(' ');
+            order_by.push_str(placement);
+        }
+        self
+    }
+
+    /// Rows of either query, duplicates removed: `(...) UNION (...)`.
+    /// Both queries must select the same columns, each keeps its own ordering and limit.
+    pub fn union(&mut self, other: &Self) -> &mut Self {
+        self.unions.push(("UNION", other.clone()));
+        self
+    }
+
+    /// Rows of both queries, duplicates kept: `(...) UNION ALL (...)`.
+    pub fn union_all(&mut self, other: &Self) -> &mut Self {
+        self.unions.push(("UNION ALL", other.clone()));
+        self
+    }
+
+    /// The pieces the builder holds so far, the conditions as they appear in `to_sql`.
+    pub fn state(&self) -> QueryBuilderState<'_> {
+        let mut placeholder = 1;
+        let conditions = self
+            .conditions
+            .conditions
+            .iter()
+            .map(|condition| condition.to_sql(&mut placeholder))
+            .collect();
+        QueryBuilderState {
+            table: T::TABLE,
+            conditions,
+            order_by: self.order_by_clause.as_deref(),
+            limit: self.limit,
+            binds: self.bind_count(),
+        }
+    }
+
+    fn bind_count(&self) -> usize {
+        let own = self.conditions.conditions.iter().map(Condition::bind_count);
+        let unions = self.unions.iter().map(|(_, other)| other.bind_count());
+        own.chain(unions).sum()
+    }
+
+    /// The SQL the builder runs, with `$n` placeholders for bound values.
+    pub fn to_sql(&self) -> String {
+        self.build().sql().to_string()
+    }
+
+    /// `to_sql` with the bound values quoted in place of the placeholders, to paste into psql
+    /// while debugging. The values are written from their Debug output, never run the result.
+    pub fn to_sql_with_binds(&self) -> String {
+        let literals = self.bind_literals();
+        let sql = self.to_sql();
+        let mut rendered = String::with_capacity(sql.len());
+        let mut rest = sql.as_str();
+        while let Some(start) = rest.find('$') {
+            let digits = rest[start + 1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - start - 1);
+            let literal = rest[start + 1..start + 1 + digits]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|i| literals.get(i));
+            match literal {
+                Some(literal) => {
+                    rendered.push_str(&rest[..start]);
+                    rendered.push_str(literal);
+                }
+                None => rendered.push_str(&rest[..start + 1 + digits]),
+            }
+            rest = &rest[start + 1 + digits..];
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    fn bind_literals(&self) -> Vec<String> {
+        let own = self
+            .conditions
+            .conditions
+            .iter()
+            .flat_map(Condition::bind_literals);
+        let unions = self
+            .unions
+            .iter()
+            .flat_map(|(_, other)| other.bind_literals());
+        own.chain(unions).collect()
+    }
+
+    fn build(&self) -> QueryBuilder<'_, Postgres> {
+        let mut query_builder = QueryBuilder::new("");
+        self.render(&mut query_builder);
+        query_builder
+    }
+
+    fn render(&self, query_builder: &mut QueryBuilder<'_, Postgres>) {
+        if !self.unions.is_empty() {
+            query_builder.push("(");
+        }
+
+        let select_list = self.select_list.as_deref().unwrap_or(T::COLUMNS);
+        if self.distinct_on.is_empty() {
+            query_builder.push(format!("SELECT {} FROM ", select_list));
+        } else {
+            query_builder.push(format!(
+                "SELECT DISTINCT ON ({}) {} FROM ",
+                self.distinct_on.join(", "),
+                select_list
+            ));
+        }
+        let alias = self.alias.as_deref().unwrap_or(T::TABLE);
+        if self.expressions.is_empty() {
+            query_builder.push(T::TABLE);
+            if self.alias.is_some() {
+                query_builder.push(format!(" AS {}", alias));
+            }
+        } else {
+            query_builder.push("(SELECT *");
+            for (name, expression) in &self.expressions {
+                query_builder.push(format!(", {} AS {}", expression, name));
+            }
+            query_builder.push(format!(" FROM {}) AS {}", T::TABLE, alias));
+        }
+        self.conditions.render_where(query_builder);
+
+        if let Some(ref order_by) = self.order_by_clause {
+            query_builder.push(" ORDER BY ");
+            query_builder.push(order_by);
+        }
+
+        if let Some(limit) = self.limit {
+            query_builder.push(format!(" LIMIT {}", limit));
+        }
+
+        if let Some(mode) = self.lock {
+            query_builder.push(" ");
+            query_builder.push(mode.as_sql());
+            if self.skip_locked {
+                query_builder.push(" SKIP LOCKED");
+            }
+        }
+
+        if self.unions.is_empty() {
+            return;
+        }
+        query_builder.push(")");
+        for (operator, other) in &self.unions {
+            query_builder.push(format!(" {} (", operator));
+            other.render(query_builder);
+            query_builder.push(")");
+        }
+    }
+
+    // Mistakes the builder methods can't return themselves, checked before executing so they
+    // show up before the query is sent. Postgres rejects DISTINCT ON unless the leftmost
+    // ORDER BY expressions are the same ones, in any order.
+    fn check(&self) -> sqlx::Result<()> {
+        for (_, other) in &self.unions {
+            other.check()?;
+        }
+        if let Some(relation) = &self.unknown_relation {
+            return Err(sqlx::Error::Configuration(Box::new(UnknownRelation {
+                table: T::TABLE,
+                relation: relation.clone(),
+            })));
+        }
+        if self.distinct_on.is_empty() {
+            return Ok(());
+        }
+        let mut leading = Vec::new();
+        if let Some(order_by) = &self.order_by_clause {
+            let mut depth = 0;
+            let mut start = 0;
+            for (i, c) in order_by.char_indices() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    ',' if depth == 0 => {
+                        leading.push(order_expression(&order_by[start..i]));
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+            leading.push(order_expression(&order_by[start..]));
+        }
+        leading.truncate(self.distinct_on.len());
+        let compatible = leading.len() == self.distinct_on.len()
+            && self
+                .distinct_on
+                .iter()
+                .all(|column| leading.contains(&column.trim()));
+        if !compatible {
+            return Err(sqlx::Error::Configuration(Box::new(InvalidDistinctOn {
+                columns: self.distinct_on.clone(),
+                order_by: self.order_by_clause.clone(),
+            })));
+        }
+        Ok(())
+    }
+
+    /// Aborts the query once it runs longer than `timeout`. The query then runs in its own
+    /// transaction, or a savepoint when given one, so `SET LOCAL statement_timeout` only
+    /// applies to it.
+    pub fn statement_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.statement_timeout = Some(timeout);
+        self
+    }
+
+    /// Runs on any pool, connection or transaction, e.g. a read replica.
+    pub async fn execute<'a>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<Vec<T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        self.execute_as::<T>(executor).await
+    }
+
+    /// Executes reading each row as `P`, for projections built with `select_raw`.
+    pub async fn execute_as<'a, P>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<Vec<P>>
+    where
+        P: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        self.check()?;
+        let Some(timeout) = self.statement_timeout else {
+            let mut connection = executor.acquire().await?;
+            return self
+                .build()
+                .build_query_as::<P>()
+                .fetch_all(&mut *connection)
+                .await;
+        };
+
+        let mut transaction = executor.begin().await?;
+        let previous: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&mut *transaction)
+            .await?;
+        // SET does not take bind parameters, the value is a plain integer so it is inlined.
+        sqlx::query(&format!(
+            "SET LOCAL statement_timeout = {}",
+            timeout.as_millis().max(1)
+        ))
+        .execute(&mut *transaction)
+        .await?;
+        let rows = self
+            .build()
+            .build_query_as::<P>()
+            .fetch_all(&mut *transaction)
+            .await?;
+        // Releasing a savepoint keeps SET LOCAL in effect until the outer transaction ends,
+        // so the caller's timeout is put back first.
+        sqlx::query("SELECT set_config('statement_timeout', $1, true)")
+            .bind(previous)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(rows)
+    }
+
+    /// The plan Postgres picks for the query, as `EXPLAIN (FORMAT TEXT)` prints it.
+    pub async fn explain<'a>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<String> {
+        self.run_explain("FORMAT TEXT", executor).await
+    }
+
+    /// Runs the query and returns the plan with the actual row counts and timings. The rows
+    /// themselves are discarded.
+    pub async fn explain_analyze<'a>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<String> {
+        self.run_explain("ANALYZE, FORMAT TEXT", executor).await
+    }
+
+    async fn run_explain<'a>(
+        &self,
+        options: &str,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<String> {
+        self.check()?;
+        let mut query_builder = QueryBuilder::new(format!("EXPLAIN ({}) ", options));
+        self.render(&mut query_builder);
+        let mut connection = executor.acquire().await?;
+        let lines: Vec<String> = query_builder
+            .build_query_scalar()
+            .fetch_all(&mut *connection)
+            .await?;
+        Ok(lines.join("\n"))
+    }
+
+    /// Rows created strictly before `value`, `created_at < $1`.
+    pub fn created_before<V>(&mut self, value: V) -> &mut Self
+    where
+        T: Timestamps,
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.filter(T::CREATED_AT, Op::Lt, value)
+    }
+
+    /// Rows created strictly after `value`, `created_at > $1`.
+    pub fn created_after<V>(&mut self, value: V) -> &mut Self
+    where
+        T: Timestamps,
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.filter(T::CREATED_AT, Op::Gt, value)
+    }
+
+    /// Rows last updated strictly before `value`, `updated_at < $1`.
+    pub fn updated_before<V>(&mut self, value: V) -> &mut Self
+    where
+        T: Timestamps,
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.filter(T::UPDATED_AT, Op::Lt, value)
+    }
+
+    /// Rows last updated strictly after `value`, `updated_at > $1`.
+    pub fn updated_after<V>(&mut self, value: V) -> &mut Self
+    where
+        T: Timestamps,
+        V: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Debug + Send + Sync + 'static,
+    {
+        self.filter(T::UPDATED_AT, Op::Gt, value)
+    }
+
+    /// Rows whose number of `relation` rows compares to `count`, e.g. authors with more than 3
+    /// posts. Counted with a correlated subquery, so it can't be combined with `alias`.
+    ///
+    /// When `T` has no `#[has_many]` relation named `relation` the query fails with
+    /// `UnknownRelation` when it runs.
+    pub fn where_relation_count(&mut self, relation: &str, op: Op, count: i64) -> &mut Self
+    where
+        T: HasMany,
+    {
+        let Some(subquery) = T::relation_count_sql(relation) else {
+            self.unknown_relation
+                .get_or_insert_with(|| String::from(relation));
+            return self;
+        };
+        self.filter(&subquery, op, count)
+    }
+
+    /// Only returns rows after the cursor, `(created, id) > ($1, $2)`.
+    pub fn after(&mut self, cursor: T::Cursor) -> &mut Self
+    where
+        T: Keyset,
+    {
+        let mut values = BindValues::default();
+        T::bind_cursor(&cursor, &mut values);
+        self.conditions.push(
+            Condition::default()
+                .sql(&format!("({}) > (", T::CURSOR_COLUMNS.join(", ")))
+                .bind_list(values)
+                .sql(")"),
+        );
+        self
+    }
+
+    /// Executes ordered by the cursor columns, returning the cursor of the last row
+    /// when the page is full.
+    pub async fn execute_page<'a>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<Page<T>>
+    where
+        T: Keyset + for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut query = self.clone();
+        query.order_by_clause = Some(T::CURSOR_COLUMNS.join(", "));
+        let rows = query.execute(executor).await?;
+
+        let next = match (self.limit, rows.last()) {
+            (Some(limit), Some(last)) if rows.len() == limit => Some(last.cursor()),
+            _ => None,
+        };
+
+        Ok(Page { rows, next })
+    }
+}
+
+impl<T: Table> Default for FindAllQueryBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// An ORDER BY item without its direction and NULLS placement.
+fn order_expression(item: &str) -> &str {
+    let mut expression = item.trim();
+    for suffix in [" NULLS FIRST", " NULLS LAST", " ASC", " DESC"] {
+        let cut = expression.len().saturating_sub(suffix.len());
+        if expression.is_char_boundary(cut) && expression[cut..].eq_ignore_ascii_case(suffix) {
+            expression = expression[..cut].trim_end();
+        }
+    }
+    expression
+}
+
+/// Returned by `into::<P>()`, a `find()` query whose rows are read as `P`.
+#[must_use = "builders do nothing unless executed"]
+pub struct Projection<T, P> {
+    query: FindAllQueryBuilder<T>,
+    row: PhantomData<fn() -> P>,
+}
+
+impl<T: Table, P> Projection<T, P> {
+    /// Selects only `columns`, NOT sanitized.
+    pub fn columns(&mut self, columns: &[&str]) -> &mut Self {
+        self.query.select_raw(&columns.join(", "));
+        self
+    }
+
+    pub fn to_sql(&self) -> String {
+        self.query.to_sql()
+    }
+
+    pub async fn execute<'a>(
+        &self,
+        executor: impl Acquire<'a, Database = Postgres>,
+    ) -> sqlx::Result<Vec<P>>
+    where
+        P: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        self.query.execute_as::<P>(executor).await
+    }
+}
+
+/// Returned by `delete_all()` and `delete_where()`.
+#[must_use = "builders do nothing unless executed"]
+pub struct DeleteAllQueryBuilder<T> {
+    conditions: Conditions,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for DeleteAllQueryBuilder<T> {
+    fn clone(&self) -> Self {
+        Self {
+            conditions: self.conditions.clone(),
+            entity: PhantomData,
+        }
+    }
+}
+
+impl<T: Table> DeleteAllQueryBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            conditions: Conditions::default(),
+            entity: PhantomData,
+        }
+    }
+
+    condition_methods!();
+
+    fn build(&self) -> QueryBuilder<'_, Postgres> {
+        let mut query_builder = QueryBuilder::new(format!("DELETE FROM {}", T::TABLE));
+        self.conditions.render_where(&mut query_builder);
+        query_builder
+    }
+
+    /// Returns the number of deleted rows.
+    pub async fn execute(&self, pool: &PgPool) -> sqlx::Result<u64> {
+        let result = self.build().build().execute(pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes with `RETURNING`, giving back the deleted rows, e.g. to record what was removed.
+    pub async fn execute_returning(&self, pool: &PgPool) -> sqlx::Result<Vec<T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut query_builder = self.build();
+        query_builder.push(format!(" RETURNING {}", T::COLUMNS));
+        query_builder.build_query_as::<T>().fetch_all(pool).await
+    }
+}
+
+impl<T: Table> Default for DeleteAllQueryBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returned by `insert_or_ignore()`, inserts with `ON CONFLICT DO NOTHING`.
+#[must_use = "builders do nothing unless executed"]
+pub struct InsertQueryBuilder<'a, T> {
+    entity: &'a T,
+    conflict_target: Option<String>,
+    conflict_predicate: Option<String>,
+    update_columns: Option<Vec<String>>,
+}
+
+impl<'a, T: Insert> InsertQueryBuilder<'a, T> {
+    pub fn new(entity: &'a T) -> Self {
+        Self {
+            entity,
+            conflict_target: None,
+            conflict_predicate: None,
+            update_columns: None,
+        }
+    }
+
+    /// Overwrites the conflicting row with the entity's values, `ON CONFLICT (...) DO UPDATE`.
+    /// Needs `on_conflict`, executing fails with `MissingConflictTarget` without it. The columns
+    /// updated default to `T::UPSERT_COLUMNS`.
+    pub fn upsert(entity: &'a T) -> Self {
+        let mut builder = Self::new(entity);
+        builder.update_only(T::UPSERT_COLUMNS);
+        builder
+    }
+
+    /// The unique columns a conflict is detected on, NOT sanitized.
+    /// Without it a conflict on any constraint is ignored, upserts can't do without it.
+    pub fn on_conflict(&mut self, columns: &str) -> &mut Self {
+        self.conflict_target = Some(String::from(columns));
+        self
+    }
+
+    /// The predicate of a partial unique index, `ON CONFLICT (...) WHERE predicate`, so the
+    /// conflict is detected on that index. NOT sanitized, needs `on_conflict`.
+    pub fn where_predicate(&mut self, predicate: &str) -> &mut Self {
+        self.conflict_predicate = Some(String::from(predicate));
+        self
+    }
+
+    /// Limits the `DO UPDATE SET` list to `columns`, NOT sanitized. The conflicting row keeps
+    /// its values for every other column, an empty list leaves it as it is.
+    pub fn update_only(&mut self, columns: &[&str]) -> &mut Self {
+        self.update_columns = Some(columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    /// The SQL the builder runs, with `$n` placeholders for bound values.
+    pub fn to_sql(&self) -> String {
+        self.build().sql().to_string()
+    }
+
+    fn build(&self) -> QueryBuilder<'a, Postgres> {
+        let mut query_builder = QueryBuilder::new(T::INSERT);
+        self.entity.push_values(&mut query_builder);
+        query_builder.push(" ON CONFLICT");
+        if let Some(columns) = &self.conflict_target {
+            query_builder.push(format!(" ({})", columns));
+            if let Some(predicate) = &self.conflict_predicate {
+                query_builder.push(format!(" WHERE {}", predicate));
+            }
+        }
+        match &self.update_columns {
+            Some(columns) if !columns.is_empty() => {
+                let assignments = columns
+                    .iter()
+                    .map(|column| format!("{} = EXCLUDED.{}", column, column))
+                    .collect::<Vec<_>>();
+                query_builder.push(format!(" DO UPDATE SET {}", assignments.join(", ")))
+            }
+            _ => query_builder.push(" DO NOTHING"),
+        };
+        query_builder.push(format!(" RETURNING {}", T::COLUMNS));
+        query_builder
+    }
+
+    /// Returns the inserted or updated row, or `None` when it conflicted and nothing was
+    /// written.
+    pub async fn execute(&self, pool: &PgPool) -> sqlx::Result<Option<T>>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let update_columns = self.update_columns.as_deref().unwrap_or_default();
+        if !update_columns.is_empty() && self.conflict_target.is_none() {
+            let missing = MissingConflictTarget {
+                update_columns: update_columns.to_vec(),
+            };
+            return Err(sqlx::Error::Configuration(Box::new(missing)));
+        }
+        self.build()
+            .build_query_as::<T>()
+            .fetch_optional(pool)
+            .await
+    }
+}
+
+/// Returned by `prepared_create()`, inserts rows one at a time on a single connection. Every row
+/// runs the same statement, so it is prepared on the first insert and reused after that.
+#[must_use = "the inserter does nothing unless used"]
+pub struct PreparedInsert<'c, T> {
+    connection: &'c mut PgConnection,
+    entity: PhantomData<fn() -> T>,
+}
+
+impl<'c, T: Insert> PreparedInsert<'c, T> {
+    pub fn new(connection: &'c mut PgConnection) -> Self {
+        Self {
+            connection,
+            entity: PhantomData,
+        }
+    }
+
+    /// Inserts the entity, returning the row as stored.
+    pub async fn insert(&mut self, entity: &T) -> sqlx::Result<T>
+    where
+        T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+    {
+        let mut query_builder = QueryBuilder::new(T::INSERT);
+        entity.push_values(&mut query_builder);
+        query_builder.push(format!(" RETURNING {}", T::COLUMNS));
+        query_builder
+            .build_query_as::<T>()
+            .persistent(true)
+            .fetch_one(&mut *self.connection)
+            .await
+    }
+}
@@ -0,0 +1,19 @@
+use std::future::Future;
+
+/// Basic persistence for an entity, implemented on `PgPool` for every `#[leviosa]` struct that
+/// isn't a view so generic code can take `&impl Repository<User>`. The key is the `id` field or the
+/// one marked `#[primary_key]`.
+pub trait Repository<T> {
+    /// The primary key, `AutoGenerated<i32>` gives `i32`.
+    type Id;
+
+    /// Inserts the entity, AutoGenerated fields are left to the database.
+    fn create(&self, entity: &T) -> impl Future<Output = sqlx::Result<T>> + Send;
+
+    fn get_by_id(&self, id: &Self::Id) -> impl Future<Output = sqlx::Result<Option<T>>> + Send;
+
+    /// Returns whether a row was deleted.
+    fn delete(&self, id: &Self::Id) -> impl Future<Output = sqlx::Result<bool>> + Send;
+
+    fn all(&self) -> impl Future<Output = sqlx::Result<Vec<T>>> + Send;
+}
@@ -0,0 +1,39 @@
+use std::future::Future;
+use std::time::Duration;
+
+/// How `with_acquire_retry` retries, the backoff doubles after every attempt.
+#[derive(Debug, Clone, Copy)]
+pub struct AcquireRetry {
+    pub attempts: u32,
+    pub backoff: Duration,
+}
+
+impl Default for AcquireRetry {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Runs `operation`, running it again when the pool timed out handing out a connection.
+/// Any other error, including errors from the query itself, is returned straight away.
+pub async fn with_acquire_retry<T, F, Fut>(retry: AcquireRetry, mut operation: F) -> sqlx::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = sqlx::Result<T>>,
+{
+    let mut backoff = retry.backoff;
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Err(sqlx::Error::PoolTimedOut) if attempt < retry.attempts => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
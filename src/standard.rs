@@ -1,313 +0,0 @@
-use inflector::Inflector;
-use proc_macro::TokenStream;
-use quote::format_ident;
-use quote::quote;
-use syn::Ident;
-use syn::{Data, DeriveInput, Fields};
-
-use crate::utils::{extract_relation_generic_type, is_field_type, type_to_string_identifier};
-
-pub fn standard_methods(name: &Ident, input: &DeriveInput) -> TokenStream {
-    let struct_name_snake_case = name.to_string().to_snake_case();
-    let methods = if let Data::Struct(data) = &input.data {
-        match &data.fields {
-            Fields::Named(fields) => fields.named.iter().map(|f| {
-                let field_name = f.ident.as_ref().unwrap();
-
-                let ty = &f.ty;
-                let get_fn_name = format_ident!("get_by_{}", field_name);
-                let update_fn_name = format_ident!("update_{}", field_name);
-                let load_relation_fn_name = format_ident!("load_{}", field_name);
-                
-              let lazy_loading_methods = if is_field_type(&f.ty, "Relation") {
-                let relation_type = extract_relation_generic_type(&f.ty).unwrap();
-                let relation_snake_case = type_to_string_identifier(relation_type).unwrap().to_snake_case();
-
-                    quote!{
-                        pub async fn #load_relation_fn_name(self, pool: &PgPool) -> sqlx::Result<Option<#relation_type>> {
-                            let query = format!("SELECT * FROM {} WHERE {} = $1", #relation_snake_case, "id");
-                            sqlx::query_as::<_, #relation_type>(&query)
-                            .bind(self.#field_name)
-                            .fetch_optional(pool).await
-
-                        }
-                    }
-                   
-                } else {
-                    quote!{}
-                };
-             
-                // Generate get_by_ and update_ methods
-                quote! {
-               
-               
-                    pub async fn #get_fn_name(pool: &sqlx::PgPool, value: &#ty) -> sqlx::Result<Option<Self>> {
-                      
-                        let query = format!("SELECT * FROM {} WHERE {} = $1", #struct_name_snake_case, stringify!(#field_name));
-                        sqlx::query_as::<_, Self>(&query)
-                            .bind(value)
-                            .fetch_optional(pool).await
-                    }
-    
-                    pub async fn #update_fn_name(&mut self, pool: &sqlx::PgPool, new_value: &#ty) -> sqlx::Result<()> {
-                        let query = format!("UPDATE {} SET {} = $2 WHERE id = $1", #struct_name_snake_case, stringify!(#field_name));
-                        sqlx::query(&query)
-                            .bind(self.id)
-                            .bind(new_value)
-                            .execute(pool).await?;
-                        self.#field_name = new_value.clone();
-                        Ok(())
-                    }
-                    #lazy_loading_methods
-                }
-            }).collect(),
-            _ => quote! {},
-        }
-    } else {
-        quote! {}
-    };
-
-    let create_method = if let Data::Struct(data) = &input.data {
-        match &data.fields {
-            Fields::Named(fields) => {
-                let field_params = fields
-                    .named
-                    .iter()
-                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated"))
-                    .map(|f| {
-                        let field_name = f.ident.as_ref().unwrap();
-                        let ty = &f.ty;
-                        quote! { #field_name: #ty }
-                    });
-
-                let field_tokens = fields
-                    .named
-                    .iter()
-                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated"))
-                    .map(|f| {
-                        let field_name = f.ident.as_ref().unwrap();
-                        quote! { #field_name }
-                    });
-
-                let joined_fields = fields
-                    .named
-                    .iter()
-                    .filter(|f| !is_field_type(&f.ty, "AutoGenerated"))
-                    .map(|f| f.ident.as_ref().unwrap().to_string())
-                    .collect::<Vec<_>>()
-                    .join(", ");
-
-                let values_str: Vec<String> = joined_fields
-                    .split(", ")
-                    .enumerate()
-                    .map(|(i, _)| format!("${}", i + 1))
-                    .collect();
-                let values_str = values_str.join(", ");
-
-                let struct_name_snake_case =
-                    format_ident!("{}", input.ident.to_string().to_snake_case());
-                let query_str = format!(
-                    "INSERT INTO {} ({}) VALUES ({}) RETURNING *",
-                    struct_name_snake_case, joined_fields, values_str
-                );
-
-                quote! {
-                    pub async fn create(
-                        pool: &sqlx::PgPool,
-                        #(#field_params),*
-                    ) -> Result<Self, sqlx::Error> {
-                        let new_entity = sqlx::query_as::<_, Self>(&#query_str)
-                            #( .bind(#field_tokens) )*
-                            .fetch_one(pool) // Execute query within the transaction
-                            .await?;
-                        Ok(new_entity)
-                    }
-                }
-            }
-            _ => quote! {},
-        }
-    } else {
-        quote! {}
-    };
-
-    let find_all_query_builder_name = format_ident!(
-        "{}FindAllQueryBuilder",
-        input.ident.to_string().to_camel_case()
-    );
-
-    let delete_all_query_builder_name = format_ident!(
-        "{}DeleteAllQueryBuilder",
-        input.ident.to_string().to_camel_case()
-    );
-
-    let find_all_query_builder = quote! {
-        #[derive(Clone)]
-        struct #find_all_query_builder_name {
-            query: String,
-            limit: Option<usize>,
-            where_clause: Option<String>,
-            order_by_clause: Option<String>
-        }
-
-        impl #find_all_query_builder_name {
-            fn new() -> Self {
-                Self {
-                    query: format!("SELECT * FROM {}", #struct_name_snake_case),
-                    limit: None,
-                    where_clause: None,
-                    order_by_clause: None
-                }
-            }
-
-            fn limit(&mut self, limit: usize) -> &mut Self {
-                self.limit = Some(limit);
-                self
-            }
-
-            fn select(&mut self, _where: &str) -> &mut Self {
-                self.where_clause = Some(String::from(_where));
-                self
-            }
-
-
-            fn order_by(&mut self, order_by: &str) -> &mut Self {
-                self.order_by_clause = Some(String::from(order_by));
-                self
-            }
-
-
-            pub async fn execute(&self, pool: &PgPool) -> sqlx::Result<Vec<#name>> {
-                let mut query = self.query.clone();
-                if let Some(ref where_clause) = self.where_clause {
-                    query.push_str(" WHERE ");
-                    query.push_str(where_clause);
-                }
-
-                if let Some(ref order_by) = self.order_by_clause {
-                    query.push_str(" ORDER BY ");
-                    query.push_str(order_by);
-                }
-
-                if let Some(limit) = self.limit {
-                    query.push_str(&format!(" LIMIT {}", limit));
-                }
-
-                sqlx::query_as::<_, #name>(&query)
-                    .fetch_all(pool)
-                    .await
-            }
-        }
-    };
-
-    let delete_all_query_builder = quote! {
-        #[derive(Clone)]
-        struct #delete_all_query_builder_name {
-            query: String,
-            where_clause: Option<String>,
-        }
-
-        impl #delete_all_query_builder_name {
-            fn new() -> Self {
-                Self {
-                    query: format!("DELETE FROM {}", #struct_name_snake_case),
-                    where_clause: None,
-                }
-            }
-
-            fn select(&mut self, _where: &str) -> &mut Self {
-                self.where_clause = Some(String::from(_where));
-                self
-            }
-
-            pub async fn execute(&self, pool: &PgPool) -> sqlx::Result<()> {
-                let mut query = self.query.clone();
-                if let Some(ref where_clause) = self.where_clause {
-                    query.push_str(" WHERE ");
-                    query.push_str(where_clause);
-                };
-
-                let mut transaction = pool.begin().await?;
-                sqlx::query(&query)
-                    .execute(&mut *transaction)
-                    .await?;
-
-                transaction.commit().await?;
-                Ok(())
-
-            }
-        }
-    };
-
-    // Define the find_all method for the struct
-    let find_all_method = quote! {
-        pub fn find() -> #find_all_query_builder_name {
-            #find_all_query_builder_name::new()
-        }
-    };
-
-    let delete_all_method = quote! {
-        pub fn delete_all() -> #delete_all_query_builder_name {
-            #delete_all_query_builder_name::new()
-        }
-    };
-
-    let constructor = if let Data::Struct(data) = &input.data {
-        match &data.fields {
-            Fields::Named(fields) => {
-                let field_params = fields.named.iter().map(|f| {
-                    let field_name = f.ident.as_ref().unwrap();
-                    let ty = &f.ty;
-                    quote! { #field_name: #ty }
-                });
-
-                // Extract field names for struct initialization
-                let field_names = fields.named.iter().map(|f| {
-                    let field_name = f.ident.as_ref().unwrap();
-                    quote! { #field_name: #field_name }
-                });
-                quote! {
-                    pub fn new(#(#field_params),*) -> Self {
-                        Self {
-                            #(#field_names),*
-                        }
-                    }
-                }
-            }
-            _ => {
-                quote! {}
-            }
-        }
-    } else {
-        quote! {}
-    };
-
-    let delete_method = quote! {
-        pub async fn delete(&mut self, pool: &sqlx::PgPool) -> sqlx::Result<()> {
-            let query = format!("DELETE FROM {} WHERE id = $1", #struct_name_snake_case);
-            sqlx::query(&query)
-                .bind(self.id)
-                .execute(pool)
-                .await?;
-            Ok(())
-        }
-    };
-
-    let standard = quote! {
-        #input
-
-        #find_all_query_builder
-        #delete_all_query_builder
-
-        impl #name {
-            #methods
-            #find_all_method
-            #delete_method
-            #delete_all_method
-            #create_method
-            #constructor
-
-        }
-    };
-
-    TokenStream::from(standard)
-}
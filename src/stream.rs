@@ -0,0 +1,31 @@
+use std::future::Future;
+
+use futures::stream::{self, Stream, StreamExt};
+
+/// Rows per statement for `insert_returning`.
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Drives the generated `insert_returning`: collects the stream into batches, inserts each batch
+/// with `insert` and yields the inserted rows in order. A failed batch yields its error.
+pub fn insert_batches<'a, T, S, F, Fut>(
+    entities: S,
+    batch_size: usize,
+    insert: F,
+) -> impl Stream<Item = sqlx::Result<T>> + 'a
+where
+    T: 'a,
+    S: Stream<Item = T> + 'a,
+    F: FnMut(Vec<T>) -> Fut + 'a,
+    Fut: Future<Output = sqlx::Result<Vec<T>>> + 'a,
+{
+    entities
+        .chunks(batch_size.max(1))
+        .then(insert)
+        .flat_map(|result| {
+            let rows: Vec<sqlx::Result<T>> = match result {
+                Ok(rows) => rows.into_iter().map(Ok).collect(),
+                Err(error) => vec![Err(error)],
+            };
+            stream::iter(rows)
+        })
+}
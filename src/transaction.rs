@@ -0,0 +1,26 @@
+use futures::future::BoxFuture;
+use sqlx::{Acquire, PgConnection, Postgres};
+
+/// Runs `operation` in a transaction, committing when it returns `Ok` and rolling back on `Err`.
+/// Given a connection already inside a transaction, e.g. from an enclosing `with_transaction`,
+/// it uses a SAVEPOINT instead, so an inner failure only rolls back the inner work.
+pub async fn with_transaction<'a, T, E, F>(
+    executor: impl Acquire<'a, Database = Postgres>,
+    operation: F,
+) -> Result<T, E>
+where
+    F: for<'t> FnOnce(&'t mut PgConnection) -> BoxFuture<'t, Result<T, E>>,
+    E: From<sqlx::Error>,
+{
+    let mut transaction = executor.begin().await?;
+    match operation(&mut transaction).await {
+        Ok(value) => {
+            transaction.commit().await?;
+            Ok(value)
+        }
+        Err(error) => {
+            transaction.rollback().await?;
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use leviosa::leviosa;
+use leviosa_utils::AutoGenerated;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[leviosa]
+#[derive(Debug, FromRow, Clone)]
+struct Token {
+    #[primary_key(generate = "uuid_v4")]
+    id: AutoGenerated<Uuid>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use leviosa::leviosa;
+use sqlx::FromRow;
+
+#[leviosa]
+#[derive(Debug, FromRow, Clone)]
+struct Wizard {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use leviosa::leviosa;
+use leviosa_utils::{AutoGenerated, Relation};
+use sqlx::FromRow;
+
+#[leviosa]
+#[derive(Debug, FromRow, Clone)]
+struct Author {
+    id: AutoGenerated<i32>,
+}
+
+#[leviosa]
+#[derive(Debug, FromRow, Clone)]
+struct Book {
+    id: AutoGenerated<i32>,
+    #[column(on_delete = "set null")]
+    author: Relation<Author>,
+}
+
+fn main() {}
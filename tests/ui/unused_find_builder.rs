@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+#![deny(unused_must_use)]
+
+use leviosa::leviosa;
+use leviosa_utils::AutoGenerated;
+use sqlx::FromRow;
+
+#[leviosa]
+#[derive(Debug, FromRow, Clone)]
+struct Wizard {
+    id: AutoGenerated<i32>,
+    name: String,
+}
+
+fn main() {
+    Wizard::find();
+}
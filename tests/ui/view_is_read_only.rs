@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+
+use leviosa::leviosa;
+use sqlx::{FromRow, PgPool};
+
+#[leviosa(view)]
+#[derive(Debug, FromRow, Clone)]
+struct WizardSummary {
+    id: i32,
+    name: String,
+}
+
+async fn write(pool: &PgPool, mut summary: WizardSummary) {
+    let _ = WizardSummary::create(pool, "Harry").await;
+    let _ = summary.update_name(pool, &String::from("Harry")).await;
+}
+
+fn main() {}